@@ -0,0 +1,512 @@
+use crate::census::find_islands;
+use crate::life_board::LifeBoard;
+use crate::life_point::LifePoint;
+use crate::pattern::Pattern;
+use crate::snapshot::BoardSnapshot;
+use crate::DynamicArray2dLifeBoard;
+use std::collections::HashMap;
+use std::fmt;
+
+/// How a pattern behaved when it was left to run.
+/// Each variant that settled records the first generation of the repeating cycle, where the starting pattern is generation 0.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Behavior {
+    /// Every cell died
+    Extinct { generation: u64 },
+    /// The pattern stopped changing
+    StillLife { generation: u64 },
+    /// The pattern returns to the same state every `period` generations
+    Oscillator { generation: u64, period: u64 },
+    /// The pattern returns to the same shape in a different place, moving at `velocity`
+    Spaceship { generation: u64, velocity: Velocity },
+    /// The pattern didn't repeat within the generation budget
+    Unsettled,
+}
+
+impl Behavior {
+    /// How many generations it takes to get back to the same shape, or `None` if it never did
+    pub fn period(&self) -> Option<u64> {
+        match self {
+            Behavior::StillLife { .. } => Some(1),
+            Behavior::Oscillator { period, .. } => Some(*period),
+            Behavior::Spaceship { velocity, .. } => Some(velocity.period),
+            Behavior::Extinct { .. } | Behavior::Unsettled => None,
+        }
+    }
+}
+
+/// How far a spaceship moves in one full period
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Velocity {
+    pub dx: i64,
+    pub dy: i64,
+    pub period: u64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Direction {
+    Orthogonal,
+    Diagonal,
+    Oblique,
+}
+
+impl Velocity {
+    pub fn direction(&self) -> Direction {
+        if self.dx == 0 || self.dy == 0 {
+            Direction::Orthogonal
+        } else if self.dx.abs() == self.dy.abs() {
+            Direction::Diagonal
+        } else {
+            Direction::Oblique
+        }
+    }
+}
+
+/// Formats the speed in the usual c-notation, eg `c/4 diagonal` for the glider or `(2,1)c/6` for an oblique ship
+impl fmt::Display for Velocity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.direction() == Direction::Oblique {
+            return write!(
+                f,
+                "({},{})c/{}",
+                self.dx.abs().max(self.dy.abs()),
+                self.dx.abs().min(self.dy.abs()),
+                self.period
+            );
+        }
+
+        let distance = self.dx.unsigned_abs().max(self.dy.unsigned_abs());
+        let divisor = gcd(distance, self.period);
+        let (distance, period) = (distance / divisor, self.period / divisor);
+        let speed = match (distance, period) {
+            (1, 1) => "c".to_owned(),
+            (1, p) => format!("c/{}", p),
+            (d, 1) => format!("{}c", d),
+            (d, p) => format!("{}c/{}", d, p),
+        };
+
+        let direction = match self.direction() {
+            Direction::Orthogonal => "orthogonal",
+            _ => "diagonal",
+        };
+        write!(f, "{} {}", speed, direction)
+    }
+}
+
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// Run the board until it repeats a previous state, or until `max_generations` steps have been taken.
+/// States are compared regardless of position so that spaceships are detected as well as oscillators.
+/// The board is left in whatever state it reached when the check finished.
+pub fn classify(board: &mut dyn LifeBoard, max_generations: u64) -> Behavior {
+    //Normalized shape -> (generation, position of the shape's top-left corner)
+    let mut seen: HashMap<BoardSnapshot, (u64, LifePoint)> = HashMap::new();
+
+    for generation in 0..=max_generations {
+        let snapshot = BoardSnapshot::of(board);
+        let corner = match snapshot.get_bounds() {
+            Some((min, _)) => min,
+            None => return Behavior::Extinct { generation },
+        };
+        let shape = snapshot.normalized();
+
+        if let Some((first_seen, first_corner)) = seen.get(&shape) {
+            let period = generation - first_seen;
+            let dx = corner.get_x() - first_corner.get_x();
+            let dy = corner.get_y() - first_corner.get_y();
+            return if dx != 0 || dy != 0 {
+                Behavior::Spaceship {
+                    generation: *first_seen,
+                    velocity: Velocity { dx, dy, period },
+                }
+            } else if period == 1 {
+                Behavior::StillLife {
+                    generation: *first_seen,
+                }
+            } else {
+                Behavior::Oscillator {
+                    generation: *first_seen,
+                    period,
+                }
+            };
+        }
+
+        seen.insert(shape, (generation, corner));
+        if generation < max_generations {
+            board.step_one();
+        }
+    }
+
+    Behavior::Unsettled
+}
+
+/// Classify a pattern by drawing it on a fresh, unbounded board
+pub fn classify_pattern(pattern: &Pattern, max_generations: u64) -> Behavior {
+    let mut board = DynamicArray2dLifeBoard::empty();
+    board.draw_pattern(pattern, &LifePoint::new(0, 0));
+    classify(&mut board, max_generations)
+}
+
+/// Run the cells on a fresh, unbounded board, returning every state including the starting one
+pub fn evolve(cells: &BoardSnapshot, generations: u64) -> Vec<BoardSnapshot> {
+    let mut board = DynamicArray2dLifeBoard::empty();
+    board.draw_pattern(&cells.to_pattern(), &LifePoint::new(0, 0));
+
+    let mut states = vec![cells.clone()];
+    for _ in 0..generations {
+        board.step_one();
+        states.push(BoardSnapshot::of(&board));
+    }
+    states
+}
+
+/// Every phase of the cycle an object settles into, starting from the first generation of that cycle.
+/// Returns `None` if it didn't settle into a still life, oscillator or spaceship.
+pub fn cycle_phases(object: &BoardSnapshot, behavior: &Behavior) -> Option<Vec<BoardSnapshot>> {
+    let start = match behavior {
+        Behavior::StillLife { generation }
+        | Behavior::Oscillator { generation, .. }
+        | Behavior::Spaceship { generation, .. } => *generation,
+        Behavior::Extinct { .. } | Behavior::Unsettled => return None,
+    };
+    let mut states = evolve(object, start + behavior.period()? - 1);
+    Some(states.split_off(start as usize))
+}
+
+/// The population has to repeat for this many generations before a pattern is considered stable
+const STABLE_WINDOW: usize = 120;
+/// The longest population cycle that counts as stable
+const MAX_STABLE_PERIOD: usize = 30;
+/// How often to look for gliders that have escaped
+const GLIDER_CHECK_INTERVAL: u64 = 32;
+/// How far a glider has to be ahead of every other cell before it's considered to have escaped
+const GLIDER_ESCAPE_DISTANCE: i64 = 20;
+const GLIDER_POPULATION: usize = 5;
+
+/// How long a pattern took to settle down, as measured by `measure_lifespan`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Lifespan {
+    /// First generation from which the population repeats periodically, or `None` if that didn't happen within the limit
+    pub stabilization_generation: Option<u64>,
+    /// Population at the stabilization generation, or at the last generation run if it never stabilized.
+    /// Escaped gliders are included.
+    pub final_population: usize,
+    /// Largest population seen, including escaped gliders
+    pub peak_population: usize,
+    /// Number of gliders that escaped from the pattern
+    pub gliders: u64,
+}
+
+/// Run the board until its population becomes periodic, or until `max_generations` steps have been taken.
+/// Gliders that escape are counted and removed from the board, so they don't keep the board growing forever.
+/// The board is left in whatever state it reached when the check finished, minus the removed gliders.
+pub fn measure_lifespan(board: &mut dyn LifeBoard, max_generations: u64) -> Lifespan {
+    let mut gliders = 0;
+    let mut populations = vec![board.get_live_cells().len()];
+
+    for generation in 1..=max_generations {
+        board.step_one();
+        if generation % GLIDER_CHECK_INTERVAL == 0 {
+            gliders += remove_escaped_gliders(board);
+        }
+        //Escaped gliders never change their population, so adding them back keeps the population periodic
+        populations.push(board.get_live_cells().len() + gliders as usize * GLIDER_POPULATION);
+
+        if let Some(start) = find_stable_start(&populations) {
+            gliders += remove_escaped_gliders(board);
+            return Lifespan {
+                stabilization_generation: Some(start as u64),
+                final_population: populations[start],
+                peak_population: *populations.iter().max().unwrap(),
+                gliders,
+            };
+        }
+    }
+
+    gliders += remove_escaped_gliders(board);
+    Lifespan {
+        stabilization_generation: None,
+        final_population: *populations.last().unwrap(),
+        peak_population: *populations.iter().max().unwrap(),
+        gliders,
+    }
+}
+
+/// Measure a pattern's lifespan by drawing it on a fresh, unbounded board
+pub fn measure_pattern_lifespan(pattern: &Pattern, max_generations: u64) -> Lifespan {
+    let mut board = DynamicArray2dLifeBoard::empty();
+    board.draw_pattern(pattern, &LifePoint::new(0, 0));
+    measure_lifespan(&mut board, max_generations)
+}
+
+/// If the most recent populations have been periodic for the whole stable window,
+/// find the first generation from which they've been periodic
+fn find_stable_start(populations: &[usize]) -> Option<usize> {
+    let latest = populations.len() - 1;
+    if latest < STABLE_WINDOW + MAX_STABLE_PERIOD {
+        return None;
+    }
+
+    let repeats = |period: usize, t: usize| populations[t] == populations[t - period];
+    let period = (1..=MAX_STABLE_PERIOD)
+        .find(|p| ((latest - STABLE_WINDOW + 1)..=latest).all(|t| repeats(*p, t)))?;
+
+    let mut start = latest - STABLE_WINDOW;
+    while start >= period && repeats(period, start) {
+        start -= 1;
+    }
+    Some(start + 1 - period)
+}
+
+/// Remove gliders that are far enough ahead of every other cell that nothing can catch them,
+/// returning how many were removed
+fn remove_escaped_gliders(board: &mut dyn LifeBoard) -> u64 {
+    let snapshot = BoardSnapshot::of(board);
+    let mut removed = 0;
+
+    for island in find_islands(&snapshot) {
+        let (dx, dy) = match glider_direction(&island) {
+            Some(direction) => direction,
+            None => continue,
+        };
+        let (min, max) = island.get_bounds().unwrap();
+        let front_x = if dx > 0 { min.get_x() } else { -max.get_x() };
+        let front_y = if dy > 0 { min.get_y() } else { -max.get_y() };
+
+        let escaped = snapshot.get_cells().iter().all(|cell| {
+            island.get_cells().binary_search(cell).is_ok()
+                || front_x - cell.get_x() * dx >= GLIDER_ESCAPE_DISTANCE
+                || front_y - cell.get_y() * dy >= GLIDER_ESCAPE_DISTANCE
+        });
+        if escaped {
+            for cell in island.get_cells() {
+                board.set_liveness_point(cell, false);
+            }
+            removed += 1;
+        }
+    }
+
+    removed
+}
+
+/// If the cells are a glider, get the direction it's travelling in as a pair of signs
+fn glider_direction(island: &BoardSnapshot) -> Option<(i64, i64)> {
+    if island.population() != GLIDER_POPULATION {
+        return None;
+    }
+
+    let later = &evolve(island, 4)[4];
+    let (start, _) = island.get_bounds()?;
+    let (end, _) = later.get_bounds()?;
+    let (dx, dy) = (end.get_x() - start.get_x(), end.get_y() - start.get_y());
+    if dx.abs() == 1 && dy.abs() == 1 && later.same_shape(island) {
+        Some((dx, dy))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::FixedBitfieldLifeBoard;
+
+    #[test]
+    pub fn block_is_still_life() {
+        assert_eq!(
+            Behavior::StillLife { generation: 0 },
+            classify_pattern(&Pattern::BLOCK(), 10)
+        );
+    }
+
+    #[test]
+    pub fn beacon_is_period_2() {
+        assert_eq!(
+            Behavior::Oscillator {
+                generation: 0,
+                period: 2
+            },
+            classify_pattern(&Pattern::BEACON(), 10)
+        );
+    }
+
+    #[test]
+    pub fn pulsar_is_period_3() {
+        assert_eq!(
+            Behavior::Oscillator {
+                generation: 0,
+                period: 3
+            },
+            classify_pattern(&Pattern::PULSAR(), 10)
+        );
+    }
+
+    #[test]
+    pub fn lone_cell_dies() {
+        let pattern = Pattern::from_tuples(vec![(0, 0)]);
+        assert_eq!(
+            Behavior::Extinct { generation: 1 },
+            classify_pattern(&pattern, 10)
+        );
+    }
+
+    #[test]
+    pub fn settling_generation_is_reported() {
+        //An L-tromino becomes a block after one generation
+        let pattern = Pattern::from_tuples(vec![(0, 0), (0, 1), (1, 1)]);
+        assert_eq!(
+            Behavior::StillLife { generation: 1 },
+            classify_pattern(&pattern, 10)
+        );
+    }
+
+    #[test]
+    pub fn cycle_phases_start_where_the_cycle_does() {
+        //The L-tromino takes one generation to become a block
+        let tromino =
+            BoardSnapshot::of_pattern(&Pattern::from_tuples(vec![(0, 0), (1, 0), (0, 1)]));
+        let behavior = classify_pattern(&tromino.to_pattern(), 10);
+        let phases = cycle_phases(&tromino, &behavior).unwrap();
+        assert_eq!(1, phases.len());
+        assert_eq!(
+            BoardSnapshot::of_pattern(&Pattern::BLOCK()),
+            phases[0].normalized()
+        );
+
+        let blinker = BoardSnapshot::of_pattern(&Pattern::BLINKER());
+        let behavior = classify_pattern(&Pattern::BLINKER(), 10);
+        assert_eq!(2, cycle_phases(&blinker, &behavior).unwrap().len());
+        assert_eq!(None, cycle_phases(&blinker, &Behavior::Unsettled));
+    }
+
+    #[test]
+    pub fn acorn_is_unsettled_within_small_budget() {
+        assert_eq!(Behavior::Unsettled, classify_pattern(&Pattern::ACORN(), 50));
+    }
+
+    #[test]
+    pub fn glider_is_diagonal_spaceship() {
+        let behavior = classify_pattern(&Pattern::GLIDER_SOUTHEAST(), 10);
+        assert_eq!(
+            Behavior::Spaceship {
+                generation: 0,
+                velocity: Velocity {
+                    dx: 1,
+                    dy: 1,
+                    period: 4
+                }
+            },
+            behavior
+        );
+
+        if let Behavior::Spaceship { velocity, .. } = behavior {
+            assert_eq!(Direction::Diagonal, velocity.direction());
+            assert_eq!("c/4 diagonal", velocity.to_string());
+        }
+    }
+
+    #[test]
+    pub fn lwss_is_orthogonal_spaceship() {
+        let lwss = Pattern::from_tuples(vec![
+            (1, 0),
+            (4, 0),
+            (0, 1),
+            (0, 2),
+            (4, 2),
+            (0, 3),
+            (1, 3),
+            (2, 3),
+            (3, 3),
+        ]);
+        match classify_pattern(&lwss, 10) {
+            Behavior::Spaceship { velocity, .. } => {
+                assert_eq!(4, velocity.period);
+                assert_eq!(-2, velocity.dx);
+                assert_eq!(0, velocity.dy);
+                assert_eq!("c/2 orthogonal", velocity.to_string());
+            }
+            other => panic!("Expected a spaceship, got {:?}", other),
+        }
+    }
+
+    #[test]
+    pub fn velocity_formatting_works() {
+        let format = |dx, dy, period| Velocity { dx, dy, period }.to_string();
+        assert_eq!("c orthogonal", format(0, 3, 3));
+        assert_eq!("2c/5 orthogonal", format(2, 0, 5));
+        assert_eq!("c/4 diagonal", format(-2, -2, 8));
+        assert_eq!("(2,1)c/6", format(-1, 2, 6));
+    }
+
+    #[test]
+    pub fn evolve_returns_every_state() {
+        let states = evolve(&BoardSnapshot::of_pattern(&Pattern::BLINKER()), 2);
+        assert_eq!(3, states.len());
+        assert_eq!(states[0], states[2]);
+        assert_ne!(states[0], states[1]);
+        assert!(states[0].same_shape(&states[1].orientations()[4]));
+    }
+
+    #[test]
+    pub fn classify_works_on_any_board() {
+        let mut board = FixedBitfieldLifeBoard::empty();
+        board.draw_pattern(&Pattern::BEACON(), &LifePoint::new(20, 20));
+        assert_eq!(
+            Behavior::Oscillator {
+                generation: 0,
+                period: 2
+            },
+            classify(&mut board, 10)
+        );
+    }
+
+    #[test]
+    pub fn acorn_lifespan_matches_catalogue() {
+        let lifespan = measure_pattern_lifespan(&Pattern::ACORN(), 10000);
+        assert_eq!(
+            Lifespan {
+                stabilization_generation: Some(5206),
+                final_population: 633,
+                peak_population: 1057,
+                gliders: 13
+            },
+            lifespan
+        );
+    }
+
+    #[test]
+    pub fn still_life_is_stable_immediately() {
+        let lifespan = measure_pattern_lifespan(&Pattern::BLOCK(), 1000);
+        assert_eq!(Some(0), lifespan.stabilization_generation);
+        assert_eq!(4, lifespan.final_population);
+        assert_eq!(0, lifespan.gliders);
+    }
+
+    #[test]
+    pub fn lone_glider_escapes() {
+        let mut board = DynamicArray2dLifeBoard::empty();
+        board.draw_pattern(&Pattern::GLIDER_SOUTHEAST(), &LifePoint::new(0, 0));
+        let lifespan = measure_lifespan(&mut board, 1000);
+        assert_eq!(Some(0), lifespan.stabilization_generation);
+        assert_eq!(5, lifespan.final_population);
+        assert_eq!(1, lifespan.gliders);
+        assert!(board.get_live_cells().is_empty());
+    }
+
+    #[test]
+    pub fn lifespan_reports_unsettled_patterns() {
+        let lifespan = measure_pattern_lifespan(&Pattern::ACORN(), 100);
+        assert_eq!(None, lifespan.stabilization_generation);
+        assert!(lifespan.peak_population >= lifespan.final_population);
+    }
+}
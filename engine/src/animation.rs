@@ -0,0 +1,203 @@
+//! Animated GIFs of a board evolving, for sharing interesting patterns outside the TUI.
+
+use crate::error::EngineError;
+use crate::life_board::LifeBoard;
+use crate::life_point::LifePoint;
+use crate::render::{image_size, render_snapshot, Color, RenderOptions};
+use crate::snapshot::BoardSnapshot;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+/// Which part of the board each frame shows
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Viewport {
+    /// The region between these top-left and bottom-right corners (inclusive)
+    Fixed(LifePoint, LifePoint),
+    /// The smallest region that holds the live cells of every generation, plus the render margin
+    AutoFit,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AnimationOptions {
+    /// Number of steps to run. The starting state is the first frame, so there is one more frame than this.
+    pub generations: u64,
+    /// Time each frame is shown for, in hundredths of a second
+    pub frame_delay: u16,
+    pub viewport: Viewport,
+    pub render: RenderOptions,
+}
+
+impl Default for AnimationOptions {
+    fn default() -> AnimationOptions {
+        AnimationOptions {
+            generations: 100,
+            frame_delay: 10,
+            viewport: Viewport::AutoFit,
+            render: RenderOptions::default(),
+        }
+    }
+}
+
+/// Run the board for the configured number of generations, writing each one as a frame of a looping GIF.
+/// The board is left at the last generation.
+pub fn write_gif<W: Write>(
+    board: &mut dyn LifeBoard,
+    options: &AnimationOptions,
+    writer: W,
+) -> io::Result<()> {
+    let mut generations = vec![BoardSnapshot::of(board)];
+    for _ in 0..options.generations {
+        board.step_one();
+        generations.push(BoardSnapshot::of(board));
+    }
+
+    let region = match options.viewport {
+        Viewport::Fixed(min, max) => (min, max),
+        Viewport::AutoFit => auto_fit(&generations, options.render.margin),
+    };
+
+    let palette = options.render.palette;
+    let colors = [palette.dead, palette.live, palette.grid];
+    let global_palette: Vec<u8> = colors.iter().flat_map(|c| [c.r, c.g, c.b]).collect();
+
+    let (width, height) = image_size(region, &options.render)?;
+    //GIFs can't be more than 65535 pixels on a side, even when the image is within the pixel limit
+    if width > u16::MAX as u32 || height > u16::MAX as u32 {
+        return Err(EngineError::InvalidImageSize {
+            width: width as u64,
+            height: height as u64,
+        }
+        .into());
+    }
+
+    let mut encoder = gif::Encoder::new(writer, width as u16, height as u16, &global_palette)
+        .map_err(io::Error::other)?;
+    encoder
+        .set_repeat(gif::Repeat::Infinite)
+        .map_err(io::Error::other)?;
+
+    for generation in &generations {
+        let image = render_snapshot(generation, Some(region), &options.render)?;
+        let mut indices = Vec::with_capacity((width * height) as usize);
+        for y in 0..height {
+            for x in 0..width {
+                indices.push(palette_index(&colors, image.get_pixel(x, y)));
+            }
+        }
+
+        let mut frame = gif::Frame::from_indexed_pixels(width as u16, height as u16, indices, None);
+        frame.delay = options.frame_delay;
+        encoder.write_frame(&frame).map_err(io::Error::other)?;
+    }
+
+    Ok(())
+}
+
+pub fn save_gif(
+    board: &mut dyn LifeBoard,
+    options: &AnimationOptions,
+    path: &Path,
+) -> io::Result<()> {
+    write_gif(board, options, BufWriter::new(File::create(path)?))
+}
+
+fn auto_fit(generations: &[BoardSnapshot], margin: i64) -> (LifePoint, LifePoint) {
+    let all_bounds: Vec<LifePoint> = generations
+        .iter()
+        .filter_map(|g| g.get_bounds())
+        .flat_map(|(min, max)| [min, max])
+        .collect();
+    let (min, max) = BoardSnapshot::of_cells(all_bounds)
+        .get_bounds()
+        .unwrap_or((LifePoint::new(0, 0), LifePoint::new(0, 0)));
+    (min.offset(-margin, -margin), max.offset(margin, margin))
+}
+
+fn palette_index(colors: &[Color], color: Color) -> u8 {
+    //Rendered images only ever use colours from the palette
+    colors.iter().position(|c| *c == color).unwrap_or(0) as u8
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::pattern::Pattern;
+    use crate::DynamicArray2dLifeBoard;
+
+    fn decode_frames(gif_data: &[u8]) -> (u16, u16, Vec<gif::Frame<'static>>) {
+        let mut decoder = gif::DecodeOptions::new().read_info(gif_data).unwrap();
+        let (width, height) = (decoder.width(), decoder.height());
+        let mut frames = vec![];
+        while let Some(frame) = decoder.read_next_frame().unwrap() {
+            frames.push(frame.clone());
+        }
+        (width, height, frames)
+    }
+
+    fn glider_gif(viewport: Viewport) -> Vec<u8> {
+        let mut board = DynamicArray2dLifeBoard::empty();
+        board.draw_pattern(&Pattern::GLIDER_SOUTHEAST(), &LifePoint::new(0, 0));
+        let options = AnimationOptions {
+            generations: 8,
+            frame_delay: 5,
+            viewport,
+            render: RenderOptions {
+                cell_size: 1,
+                margin: 0,
+                ..RenderOptions::default()
+            },
+        };
+        let mut gif_data = vec![];
+        write_gif(&mut board, &options, &mut gif_data).unwrap();
+        gif_data
+    }
+
+    #[test]
+    pub fn every_generation_is_a_frame() {
+        let (_, _, frames) = decode_frames(&glider_gif(Viewport::AutoFit));
+        assert_eq!(9, frames.len());
+        assert!(frames.iter().all(|f| f.delay == 5));
+    }
+
+    #[test]
+    pub fn auto_fit_covers_the_whole_run() {
+        //The glider is 3x3 and moves two cells diagonally in 8 generations
+        let (width, height, _) = decode_frames(&glider_gif(Viewport::AutoFit));
+        assert_eq!((5, 5), (width, height));
+    }
+
+    #[test]
+    pub fn fixed_viewport_is_respected() {
+        let viewport = Viewport::Fixed(LifePoint::new(-10, -10), LifePoint::new(9, 4));
+        let (width, height, frames) = decode_frames(&glider_gif(viewport));
+        assert_eq!((20, 15), (width, height));
+        assert_eq!(9, frames.len());
+    }
+
+    #[test]
+    pub fn oversized_gifs_are_rejected() {
+        //Well within the pixel limit, but wider than a GIF can be
+        let mut board = DynamicArray2dLifeBoard::empty();
+        let options = AnimationOptions {
+            generations: 0,
+            viewport: Viewport::Fixed(LifePoint::new(0, 0), LifePoint::new(99_999, 0)),
+            render: RenderOptions {
+                cell_size: 1,
+                ..RenderOptions::default()
+            },
+            ..AnimationOptions::default()
+        };
+        let error = write_gif(&mut board, &options, vec![]).unwrap_err();
+        assert_eq!(io::ErrorKind::InvalidInput, error.kind());
+        assert_eq!(
+            Some(&EngineError::InvalidImageSize {
+                width: 100_000,
+                height: 1
+            }),
+            error
+                .get_ref()
+                .and_then(|e| e.downcast_ref::<EngineError>())
+        );
+    }
+}
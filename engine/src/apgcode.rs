@@ -0,0 +1,291 @@
+//! Encoding and decoding of apgcodes, the object identifiers used by apgsearch and the community catalogues.
+//! An apgcode is a prefix giving the kind of object, followed by its shape in extended Wechsler format,
+//! eg `xs4_33` for the block, `xp2_7` for the blinker and `xq4_153` for the glider.
+//! See <https://conwaylife.com/wiki/Apgcode>
+
+use crate::analysis::{classify_pattern, cycle_phases, Behavior};
+use crate::error::EngineError;
+use crate::life_point::LifePoint;
+use crate::pattern::Pattern;
+use crate::snapshot::BoardSnapshot;
+
+/// How long a pattern is run for when working out if it's a still life, oscillator or spaceship
+const CLASSIFY_GENERATIONS: u64 = 256;
+
+/// Column values, with bit 0 as the top row of a five-row strip
+const COLUMN_CHARS: &[u8] = b"0123456789abcdefghijklmnopqrstuv";
+/// Counts for runs of 4 to 39 blank columns, following a `y`
+const RUN_CHARS: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+
+/// Get the apgcode of a pattern, or `None` if it isn't a still life, oscillator or spaceship
+pub fn encode(pattern: &Pattern) -> Option<String> {
+    let behavior = classify_pattern(pattern, CLASSIFY_GENERATIONS);
+    encode_object(&BoardSnapshot::of_pattern(pattern), &behavior)
+}
+
+/// Get the apgcode of an object that has already been classified.
+/// The code describes the cycle the object settles into, so objects that haven't reached it yet are run forward first.
+pub fn encode_object(object: &BoardSnapshot, behavior: &Behavior) -> Option<String> {
+    let phases = cycle_phases(object, behavior)?;
+    let prefix = match behavior {
+        Behavior::StillLife { .. } => format!("xs{}", phases[0].population()),
+        Behavior::Oscillator { period, .. } => format!("xp{}", period),
+        Behavior::Spaceship { velocity, .. } => format!("xq{}", velocity.period),
+        Behavior::Extinct { .. } | Behavior::Unsettled => return None,
+    };
+
+    //Every phase in every orientation is a candidate. The shortest code wins, with ties going to the first alphabetically.
+    let body = phases
+        .iter()
+        .flat_map(|phase| phase.orientations())
+        .map(|candidate| encode_wechsler(&candidate))
+        .min_by(|a, b| a.len().cmp(&b.len()).then_with(|| a.cmp(b)))?;
+
+    Some(format!("{}_{}", prefix, body))
+}
+
+/// Encode the cells exactly as they are, without looking for a canonical phase or orientation.
+/// The cells are moved so that the top-left corner of their bounding box is at the origin.
+pub fn encode_wechsler(snapshot: &BoardSnapshot) -> String {
+    let (_, max) = match snapshot.normalized().get_bounds() {
+        Some(bounds) => bounds,
+        None => return String::new(),
+    };
+    let width = (max.get_x() + 1) as usize;
+    let strip_count = (max.get_y() / 5 + 1) as usize;
+
+    let mut strips: Vec<Vec<u8>> = vec![vec![0; width]; strip_count];
+    for cell in snapshot.normalized().get_cells() {
+        let strip = (cell.get_y() / 5) as usize;
+        strips[strip][cell.get_x() as usize] |= 1 << (cell.get_y() % 5);
+    }
+
+    strips
+        .iter()
+        .map(|columns| encode_strip(columns))
+        .collect::<Vec<String>>()
+        .join("z")
+}
+
+fn encode_strip(columns: &[u8]) -> String {
+    let used = columns.iter().rposition(|c| *c != 0).map_or(0, |i| i + 1);
+    let mut encoded = String::new();
+    let mut blanks = 0;
+    for column in &columns[..used] {
+        if *column == 0 {
+            blanks += 1;
+        } else {
+            encode_blanks(&mut encoded, blanks);
+            blanks = 0;
+            encoded.push(COLUMN_CHARS[*column as usize] as char);
+        }
+    }
+    encoded
+}
+
+fn encode_blanks(encoded: &mut String, mut blanks: usize) {
+    while blanks > 0 {
+        match blanks {
+            1 => encoded.push('0'),
+            2 => encoded.push('w'),
+            3 => encoded.push('x'),
+            _ => {
+                let run = blanks.min(39);
+                encoded.push('y');
+                encoded.push(RUN_CHARS[run - 4] as char);
+                blanks -= run;
+                continue;
+            }
+        }
+        return;
+    }
+}
+
+/// Turn an apgcode back into a pattern, with the top-left corner of its bounding box at the origin.
+/// The `xs`, `xp` and `xq` prefixes are supported, and a bare Wechsler string without any prefix is accepted too.
+pub fn decode(code: &str) -> Result<Pattern, EngineError> {
+    let body = match code.split_once('_') {
+        Some((prefix, body)) => {
+            let kind = prefix.get(..2).unwrap_or("");
+            let number = prefix.get(2..).unwrap_or("");
+            if !["xs", "xp", "xq"].contains(&kind)
+                || number.is_empty()
+                || !number.chars().all(|c| c.is_ascii_digit())
+            {
+                return Err(EngineError::UnsupportedApgcodePrefix(prefix.to_owned()));
+            }
+            body
+        }
+        None => code,
+    };
+
+    decode_wechsler(body)
+}
+
+fn decode_wechsler(body: &str) -> Result<Pattern, EngineError> {
+    let invalid = |reason: String| EngineError::InvalidApgcode {
+        code: body.to_owned(),
+        reason,
+    };
+    let mut cells = vec![];
+    let (mut x, mut strip): (i64, i64) = (0, 0);
+    let mut chars = body.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            'w' => x += 2,
+            'x' => x += 3,
+            'y' => {
+                let run = chars
+                    .next()
+                    .and_then(|r| RUN_CHARS.iter().position(|rc| *rc as char == r))
+                    .ok_or_else(|| invalid("Invalid blank run after 'y'".to_owned()))?;
+                x += run as i64 + 4;
+            }
+            'z' => {
+                x = 0;
+                strip += 1;
+            }
+            _ => {
+                let column = COLUMN_CHARS
+                    .iter()
+                    .position(|cc| *cc as char == c)
+                    .ok_or_else(|| invalid(format!("Invalid character '{}'", c)))?;
+                for bit in 0..5 {
+                    if column & (1 << bit) != 0 {
+                        cells.push(LifePoint::new(x, strip * 5 + bit));
+                    }
+                }
+                x += 1;
+            }
+        }
+    }
+
+    Ok(Pattern::new(cells))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::analysis::evolve;
+
+    fn encode_named(pattern: Pattern) -> String {
+        encode(&pattern).unwrap()
+    }
+
+    #[test]
+    pub fn still_lifes_match_catalogue() {
+        assert_eq!("xs4_33", encode_named(Pattern::BLOCK()));
+        assert_eq!("xs6_696", encode_named(Pattern::BEEHIVE()));
+        assert_eq!("xs7_2596", encode_named(Pattern::LOAF()));
+        assert_eq!("xs5_253", encode_named(Pattern::BOAT()));
+        assert_eq!("xs6_356", encode_named(Pattern::SHIP()));
+        assert_eq!("xs4_252", encode_named(Pattern::TUB()));
+        assert_eq!("xs8_6996", encode_named(Pattern::POND()));
+    }
+
+    #[test]
+    pub fn oscillators_match_catalogue() {
+        assert_eq!("xp2_7", encode_named(Pattern::BLINKER()));
+        assert_eq!("xp2_7e", encode_named(Pattern::TOAD()));
+        assert_eq!("xp2_318c", encode_named(Pattern::BEACON()));
+    }
+
+    #[test]
+    pub fn spaceships_match_catalogue() {
+        assert_eq!("xq4_153", encode_named(Pattern::GLIDER_SOUTHEAST()));
+    }
+
+    #[test]
+    pub fn encoding_ignores_position_orientation_and_phase() {
+        let moved = BoardSnapshot::of_pattern(&Pattern::GLIDER_SOUTHEAST()).translated(-30, 12);
+        let rotated = &moved.orientations()[5];
+        let advanced = &evolve(rotated, 3)[3];
+        assert_eq!(Some("xq4_153".to_owned()), encode(&advanced.to_pattern()));
+    }
+
+    #[test]
+    pub fn objects_are_encoded_once_settled() {
+        //An L-tromino becomes a block, and a stray cell below a blinker dies off and leaves just the blinker
+        assert_eq!(
+            "xs4_33",
+            encode_named(Pattern::from_tuples(vec![(0, 0), (1, 0), (0, 1)]))
+        );
+        assert_eq!(
+            "xp2_7",
+            encode_named(Pattern::from_tuples(vec![(0, 0), (1, 0), (2, 0), (0, 3)]))
+        );
+    }
+
+    #[test]
+    pub fn unsettled_patterns_have_no_code() {
+        assert_eq!(None, encode(&Pattern::from_tuples(vec![(0, 0)])));
+    }
+
+    #[test]
+    pub fn decode_works() {
+        let glider = decode("xq4_153").unwrap();
+        assert_eq!(
+            BoardSnapshot::of_pattern(&glider),
+            BoardSnapshot::of_pattern(&Pattern::from_tuples(vec![
+                (0, 0),
+                (1, 0),
+                (2, 0),
+                (2, 1),
+                (1, 2)
+            ]))
+        );
+    }
+
+    #[test]
+    pub fn decode_handles_blanks_and_strips() {
+        //Two cells in row 0, nine columns apart, then one in row 5 of the second strip
+        let pattern = decode("1y51z1").unwrap();
+        assert_eq!(
+            BoardSnapshot::of_pattern(&pattern),
+            BoardSnapshot::of_pattern(&Pattern::from_tuples(vec![(0, 0), (10, 0), (0, 5)]))
+        );
+        assert_eq!(
+            "1y51z1",
+            encode_wechsler(&BoardSnapshot::of_pattern(&pattern))
+        );
+    }
+
+    #[test]
+    pub fn wechsler_round_trips() {
+        for code in ["1x1", "1yz1", "1yzx1", "11zz11", "1g0g", "1z01"] {
+            let pattern = decode(code).unwrap();
+            assert_eq!(code, encode_wechsler(&BoardSnapshot::of_pattern(&pattern)));
+        }
+    }
+
+    #[test]
+    pub fn apgcode_round_trips() {
+        for pattern in [Pattern::PULSAR(), Pattern::ACORN(), Pattern::LOAF()] {
+            let snapshot = BoardSnapshot::of_pattern(&pattern).normalized();
+            let decoded = decode(&encode_wechsler(&snapshot)).unwrap();
+            assert_eq!(snapshot, BoardSnapshot::of_pattern(&decoded));
+        }
+
+        let pulsar = encode(&Pattern::PULSAR()).unwrap();
+        assert!(pulsar.starts_with("xp3_"));
+        let decoded = decode(&pulsar).unwrap();
+        assert_eq!(Some(pulsar), encode(&decoded));
+    }
+
+    #[test]
+    pub fn decode_rejects_bad_input() {
+        assert!(decode("xs4_3!").is_err());
+        assert!(decode("xs4_1y").is_err());
+        assert!(decode("ov_s16").is_err());
+        assert!(decode("xs_33").is_err());
+        assert_eq!(
+            Err(EngineError::UnsupportedApgcodePrefix("ov".to_owned())),
+            decode("ov_s16")
+        );
+        assert_eq!(
+            "Invalid character '!' in '3!'",
+            decode("xs4_3!").unwrap_err().to_string()
+        );
+    }
+}
@@ -11,6 +11,7 @@ impl<T> ArrayGrid<T>
 where
     T: Clone + Default,
 {
+    #[allow(dead_code)]
     pub fn set(&mut self, xu: usize, yu: usize, val: T) {
         let idx = self.get_index(xu, yu);
         self.arr[idx] = val;
@@ -30,7 +31,7 @@ where
         yu + xu * self.col_size
     }
 
-    pub fn iter(&self) -> std::slice::Iter<T> {
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
         self.arr.iter()
     }
 
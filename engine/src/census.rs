@@ -0,0 +1,383 @@
+use crate::analysis::{classify_pattern, cycle_phases, evolve, Behavior};
+use crate::apgcode;
+use crate::life_board::LifeBoard;
+use crate::life_point::LifePoint;
+use crate::pattern::Pattern;
+use crate::snapshot::BoardSnapshot;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::OnceLock;
+
+/// How long each object is run for when working out what it is
+const CLASSIFY_GENERATIONS: u64 = 256;
+/// Limits on how long two neighboring objects are run side-by-side to see if they interact.
+/// They're run for two full cycles within these limits, as some pairs only differ from running apart after a while.
+const MIN_INTERACTION_GENERATIONS: u64 = 4;
+const MAX_INTERACTION_GENERATIONS: u64 = 60;
+/// Objects with bounding boxes further apart than this are never checked for interactions
+const INTERACTION_DISTANCE: i64 = 4;
+
+/// One kind of object found by a census, and how many times it was seen
+#[derive(Clone, Debug)]
+pub struct CensusEntry {
+    /// The common name of the object, if it's one of the well-known ones in `Pattern`
+    pub name: Option<&'static str>,
+    /// The apgcode used by community catalogues, if the object settled into a still life, oscillator or spaceship
+    pub apgcode: Option<String>,
+    /// The object in its canonical phase and orientation, moved to the origin
+    pub canonical_form: BoardSnapshot,
+    pub behavior: Behavior,
+    pub count: u64,
+}
+
+/// A count of the separate objects on one or more boards, which are expected to have already settled down.
+/// Objects are identified by their canonical form, so rotated, reflected, moved or out-of-phase copies of an object are counted together.
+#[derive(Clone, Debug, Default)]
+pub struct Census {
+    entries: HashMap<BoardSnapshot, CensusEntry>,
+}
+
+impl Census {
+    pub fn empty() -> Census {
+        Census::default()
+    }
+
+    /// Split the board's live cells into objects and count them
+    pub fn take(board: &dyn LifeBoard) -> Census {
+        let mut census = Census::empty();
+        for object in split_objects(&BoardSnapshot::of(board)) {
+            census.add_object(&object);
+        }
+        census
+    }
+
+    /// Identify a single object and add it to the counts
+    pub fn add_object(&mut self, object: &BoardSnapshot) {
+        let behavior = classify_pattern(&object.to_pattern(), CLASSIFY_GENERATIONS);
+        let canonical_form = canonical_form(object, &behavior);
+        self.entries
+            .entry(canonical_form.clone())
+            .or_insert_with(|| CensusEntry {
+                name: known_objects().get(&canonical_form).copied(),
+                apgcode: apgcode::encode_object(object, &behavior),
+                canonical_form,
+                behavior,
+                count: 0,
+            })
+            .count += 1;
+    }
+
+    /// Add the counts from another census into this one
+    pub fn merge(&mut self, other: &Census) {
+        for (key, entry) in &other.entries {
+            self.entries
+                .entry(key.clone())
+                .or_insert_with(|| CensusEntry {
+                    count: 0,
+                    ..entry.clone()
+                })
+                .count += entry.count;
+        }
+    }
+
+    /// Get every kind of object seen, most common first
+    pub fn get_entries(&self) -> Vec<&CensusEntry> {
+        let mut entries: Vec<&CensusEntry> = self.entries.values().collect();
+        entries.sort_by(|a, b| {
+            b.count
+                .cmp(&a.count)
+                .then_with(|| a.canonical_form.cmp(&b.canonical_form))
+        });
+        entries
+    }
+
+    /// Count the objects with the same shape as this pattern, in any phase or orientation
+    pub fn count_of(&self, pattern: &Pattern) -> u64 {
+        let object = BoardSnapshot::of_pattern(pattern);
+        let behavior = classify_pattern(pattern, CLASSIFY_GENERATIONS);
+        let key = canonical_form(&object, &behavior);
+        self.entries.get(&key).map(|e| e.count).unwrap_or(0)
+    }
+
+    pub fn count_named(&self, name: &str) -> u64 {
+        self.entries
+            .values()
+            .filter(|e| e.name == Some(name))
+            .map(|e| e.count)
+            .sum()
+    }
+
+    pub fn count_apgcode(&self, code: &str) -> u64 {
+        self.entries
+            .values()
+            .filter(|e| e.apgcode.as_deref() == Some(code))
+            .map(|e| e.count)
+            .sum()
+    }
+
+    pub fn total_objects(&self) -> u64 {
+        self.entries.values().map(|e| e.count).sum()
+    }
+}
+
+/// Split the live cells into separate objects.
+/// Cells start out grouped into islands of touching cells. Nearby islands are then run together and separately,
+/// and merged if that gives a different result. That keeps pseudo-objects like a pair of blocks as two objects,
+/// while objects made of several islands that support each other (eg the beacon) stay whole.
+pub fn split_objects(snapshot: &BoardSnapshot) -> Vec<BoardSnapshot> {
+    let mut objects = find_islands(snapshot);
+    let mut periods: Vec<u64> = objects.iter().map(find_period).collect();
+
+    'search: loop {
+        for i in 0..objects.len() {
+            for j in (i + 1)..objects.len() {
+                if !are_near(&objects[i], &objects[j]) {
+                    continue;
+                }
+
+                let generations = (2 * lcm(periods[i], periods[j]))
+                    .clamp(MIN_INTERACTION_GENERATIONS, MAX_INTERACTION_GENERATIONS);
+                if interacts(&objects[i], &objects[j], generations) {
+                    let other = objects.remove(j);
+                    periods.remove(j);
+                    objects[i] = union(&objects[i], &other);
+                    periods[i] = find_period(&objects[i]);
+                    continue 'search;
+                }
+            }
+        }
+        break;
+    }
+
+    objects
+}
+
+/// Group the cells into sets that touch each other, including diagonally
+pub(crate) fn find_islands(snapshot: &BoardSnapshot) -> Vec<BoardSnapshot> {
+    let mut remaining: HashSet<LifePoint> = snapshot.get_cells().iter().copied().collect();
+    let mut islands = vec![];
+
+    //Iterate the sorted cells so the output order is deterministic
+    for start in snapshot.get_cells() {
+        if !remaining.remove(start) {
+            continue;
+        }
+
+        let mut island = vec![*start];
+        let mut queue = VecDeque::from(vec![*start]);
+        while let Some(cell) = queue.pop_front() {
+            for dx in -1..=1 {
+                for dy in -1..=1 {
+                    let neighbor = cell.offset(dx, dy);
+                    if remaining.remove(&neighbor) {
+                        island.push(neighbor);
+                        queue.push_back(neighbor);
+                    }
+                }
+            }
+        }
+        islands.push(BoardSnapshot::of_cells(island));
+    }
+
+    islands
+}
+
+fn are_near(a: &BoardSnapshot, b: &BoardSnapshot) -> bool {
+    match (a.get_bounds(), b.get_bounds()) {
+        (Some((a_min, a_max)), Some((b_min, b_max))) => {
+            let x_gap = (b_min.get_x() - a_max.get_x()).max(a_min.get_x() - b_max.get_x());
+            let y_gap = (b_min.get_y() - a_max.get_y()).max(a_min.get_y() - b_max.get_y());
+            x_gap <= INTERACTION_DISTANCE && y_gap <= INTERACTION_DISTANCE
+        }
+        _ => false,
+    }
+}
+
+/// Check if running the two sets of cells together differs from running each on its own
+fn interacts(a: &BoardSnapshot, b: &BoardSnapshot, generations: u64) -> bool {
+    let a_states = evolve(a, generations);
+    let b_states = evolve(b, generations);
+    let together = evolve(&union(a, b), generations);
+
+    together
+        .iter()
+        .zip(a_states.iter().zip(b_states.iter()))
+        .any(|(both, (a, b))| *both != union(a, b))
+}
+
+fn union(a: &BoardSnapshot, b: &BoardSnapshot) -> BoardSnapshot {
+    let mut cells = a.get_cells().clone();
+    cells.extend(b.get_cells());
+    BoardSnapshot::of_cells(cells)
+}
+
+/// How many distinct phases an object has. Anything that didn't settle is treated as having only the one we saw.
+fn find_period(object: &BoardSnapshot) -> u64 {
+    classify_pattern(&object.to_pattern(), CLASSIFY_GENERATIONS)
+        .period()
+        .unwrap_or(1)
+}
+
+fn lcm(a: u64, b: u64) -> u64 {
+    let (mut x, mut y) = (a, b);
+    while y != 0 {
+        (x, y) = (y, x % y);
+    }
+    a / x * b
+}
+
+/// The smallest normalized snapshot out of every phase of the object's cycle in each of the eight orientations.
+/// Objects that never settled into a cycle are taken as they are.
+fn canonical_form(object: &BoardSnapshot, behavior: &Behavior) -> BoardSnapshot {
+    cycle_phases(object, behavior)
+        .unwrap_or_else(|| vec![object.clone()])
+        .iter()
+        .flat_map(|phase| phase.orientations())
+        .min()
+        .unwrap_or_else(|| BoardSnapshot::of_cells(vec![]))
+}
+
+/// Canonical forms of the named objects in the `Pattern` library
+fn known_objects() -> &'static HashMap<BoardSnapshot, &'static str> {
+    static KNOWN_OBJECTS: OnceLock<HashMap<BoardSnapshot, &'static str>> = OnceLock::new();
+    KNOWN_OBJECTS.get_or_init(|| {
+        let patterns = vec![
+            ("block", Pattern::BLOCK()),
+            ("blinker", Pattern::BLINKER()),
+            ("beehive", Pattern::BEEHIVE()),
+            ("loaf", Pattern::LOAF()),
+            ("boat", Pattern::BOAT()),
+            ("ship", Pattern::SHIP()),
+            ("tub", Pattern::TUB()),
+            ("pond", Pattern::POND()),
+            ("toad", Pattern::TOAD()),
+            ("beacon", Pattern::BEACON()),
+            ("pulsar", Pattern::PULSAR()),
+            ("glider", Pattern::GLIDER_SOUTHEAST()),
+        ];
+
+        patterns
+            .into_iter()
+            .map(|(name, pattern)| {
+                let behavior = classify_pattern(&pattern, CLASSIFY_GENERATIONS);
+                (
+                    canonical_form(&BoardSnapshot::of_pattern(&pattern), &behavior),
+                    name,
+                )
+            })
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{DynamicArray2dLifeBoard, FixedBitfieldLifeBoard, FixedVectorLifeBoard};
+
+    #[test]
+    pub fn census_counts_separate_objects() {
+        let mut board = DynamicArray2dLifeBoard::empty();
+        board.draw_pattern(&Pattern::BLOCK(), &LifePoint::new(0, 0));
+        board.draw_pattern(&Pattern::BLOCK(), &LifePoint::new(20, -5));
+        board.draw_pattern(&Pattern::BLINKER(), &LifePoint::new(-20, 0));
+        board.draw_pattern(&Pattern::BEEHIVE(), &LifePoint::new(0, 30));
+        board.draw_pattern(&Pattern::GLIDER_SOUTHEAST(), &LifePoint::new(40, 40));
+
+        let census = Census::take(&board);
+        assert_eq!(5, census.total_objects());
+        assert_eq!(2, census.count_named("block"));
+        assert_eq!(1, census.count_named("blinker"));
+        assert_eq!(1, census.count_named("beehive"));
+        assert_eq!(1, census.count_named("glider"));
+        assert_eq!(2, census.count_of(&Pattern::BLOCK()));
+        assert_eq!(2, census.count_apgcode("xs4_33"));
+        assert_eq!(1, census.count_apgcode("xq4_153"));
+
+        let entries = census.get_entries();
+        assert_eq!(Some("block"), entries[0].name);
+        assert_eq!(Some("xs4_33"), entries[0].apgcode.as_deref());
+        assert_eq!(Behavior::StillLife { generation: 0 }, entries[0].behavior);
+    }
+
+    #[test]
+    pub fn census_ignores_orientation_and_phase() {
+        let mut board = FixedVectorLifeBoard::empty();
+        board.draw_pattern(&Pattern::BLINKER(), &LifePoint::new(10, 10));
+        board.draw_pattern(&Pattern::BLINKER(), &LifePoint::new(30, 30));
+        //Turn the second blinker on its side
+        board.set_liveness(29, 30, false);
+        board.set_liveness(31, 30, false);
+        board.set_live(30, 29);
+        board.set_live(30, 31);
+        board.draw_pattern(&Pattern::GLIDER_SOUTHEAST(), &LifePoint::new(80, 80));
+        board.step_one();
+        board.draw_pattern(&Pattern::GLIDER_SOUTHEAST(), &LifePoint::new(100, 100));
+
+        let census = Census::take(&board);
+        assert_eq!(2, census.count_named("blinker"));
+        assert_eq!(2, census.count_named("glider"));
+        assert_eq!(2, census.get_entries().len());
+    }
+
+    #[test]
+    pub fn pseudo_objects_are_split() {
+        //Two blocks one cell apart form a stable bi-block, but it's really two blocks
+        let mut board = FixedBitfieldLifeBoard::empty();
+        board.draw_pattern(&Pattern::BLOCK(), &LifePoint::new(10, 10));
+        board.draw_pattern(&Pattern::BLOCK(), &LifePoint::new(13, 10));
+
+        let census = Census::take(&board);
+        assert_eq!(2, census.total_objects());
+        assert_eq!(2, census.count_named("block"));
+    }
+
+    #[test]
+    pub fn supporting_islands_are_kept_together() {
+        //In this phase the beacon is two separate islands, each of which would turn into a block on its own
+        let mut board = DynamicArray2dLifeBoard::empty();
+        board.draw_pattern(&Pattern::BEACON(), &LifePoint::new(0, 0));
+        board.step_one();
+        assert_eq!(2, find_islands(&BoardSnapshot::of(&board)).len());
+
+        let census = Census::take(&board);
+        assert_eq!(1, census.total_objects());
+        assert_eq!(1, census.count_named("beacon"));
+    }
+
+    #[test]
+    pub fn merge_adds_counts() {
+        let mut board = DynamicArray2dLifeBoard::empty();
+        board.draw_pattern(&Pattern::BLOCK(), &LifePoint::new(0, 0));
+        let mut census = Census::take(&board);
+
+        board.draw_pattern(&Pattern::TUB(), &LifePoint::new(10, 0));
+        census.merge(&Census::take(&board));
+
+        assert_eq!(2, census.count_named("block"));
+        assert_eq!(1, census.count_named("tub"));
+        assert_eq!(3, census.total_objects());
+    }
+
+    #[test]
+    pub fn objects_are_counted_by_what_they_settle_into() {
+        //An L-tromino becomes a block after one generation, so it's counted as one
+        let mut census = Census::empty();
+        census.add_object(&BoardSnapshot::of_pattern(&Pattern::from_tuples(vec![
+            (0, 0),
+            (1, 0),
+            (0, 1),
+        ])));
+        census.add_object(&BoardSnapshot::of_pattern(&Pattern::BLOCK()));
+        assert_eq!(1, census.get_entries().len());
+        assert_eq!(2, census.count_named("block"));
+        assert_eq!(2, census.count_apgcode("xs4_33"));
+        assert_eq!(
+            2,
+            census.count_of(&Pattern::from_tuples(vec![(1, 1), (1, 0), (0, 1)]))
+        );
+    }
+
+    #[test]
+    pub fn library_objects_have_distinct_forms() {
+        assert_eq!(12, known_objects().len());
+    }
+}
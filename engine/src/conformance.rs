@@ -0,0 +1,390 @@
+//! Scenarios run against every board in the registry, checking that they all agree generation by generation.
+//! New implementations are covered as soon as they're added to the registry.
+
+use crate::life_board::LifeBoard;
+use crate::life_point::LifePoint;
+use crate::pattern::Pattern;
+use crate::rectangle::Rectangle;
+use crate::registry::{available_boards, BoardInfo};
+use crate::snapshot::BoardSnapshot;
+use crate::soup::{generate_soup, Symmetry};
+
+/// Draw the pattern at `at` and run it, giving every generation (including the first) relative to `at`
+fn run(info: &BoardInfo, pattern: &Pattern, at: LifePoint, generations: u64) -> Vec<BoardSnapshot> {
+    let mut board = info.create();
+    board.draw_pattern(pattern, &at);
+    let mut history = vec![relative_snapshot(board.as_ref(), at)];
+    for _ in 0..generations {
+        board.step_one();
+        history.push(relative_snapshot(board.as_ref(), at));
+    }
+    history
+}
+
+fn relative_snapshot(board: &dyn LifeBoard, at: LifePoint) -> BoardSnapshot {
+    BoardSnapshot::of(board).translated(-at.get_x(), -at.get_y())
+}
+
+/// Run the pattern on an unbounded board, killing every cell outside `bounds` after each step.
+/// This is what a fixed board should do, worked out without any fixed board's code.
+fn run_clipped(
+    pattern: &Pattern,
+    at: LifePoint,
+    generations: u64,
+    bounds: Rectangle,
+) -> Vec<BoardSnapshot> {
+    let mut board = unbounded_boards()[0].create();
+    board.draw_pattern(pattern, &at);
+    let mut history = vec![relative_snapshot(board.as_ref(), at)];
+    for _ in 0..generations {
+        board.step_one();
+        for cell in board.get_live_cells() {
+            if !bounds.contains(&cell) {
+                board.set_liveness_point(&cell, false);
+            }
+        }
+        history.push(relative_snapshot(board.as_ref(), at));
+    }
+    history
+}
+
+/// Run the pattern on each board, placed by `position`, and check every board's history matches `expected`
+fn assert_match(
+    expected: &[BoardSnapshot],
+    expected_name: &str,
+    boards: &[&BoardInfo],
+    pattern: &Pattern,
+    position: impl Fn(&BoardInfo) -> LifePoint,
+    scenario: &str,
+) {
+    let generations = expected.len() as u64 - 1;
+    for info in boards {
+        let actual = run(info, pattern, position(info), generations);
+        for (generation, (e, a)) in expected.iter().zip(actual.iter()).enumerate() {
+            assert_eq!(
+                e, a,
+                "{}: {} and {} differ at generation {}",
+                scenario, expected_name, info.name, generation
+            );
+        }
+    }
+}
+
+/// Run the pattern on each board, placed by `position`, and check every board matches the first one.
+/// Returns the first board's history for any further checks.
+fn assert_agree(
+    boards: &[&BoardInfo],
+    pattern: &Pattern,
+    position: impl Fn(&BoardInfo) -> LifePoint,
+    generations: u64,
+    scenario: &str,
+) -> Vec<BoardSnapshot> {
+    let (first, rest) = boards.split_first().expect("No boards to compare");
+    let expected = run(first, pattern, position(first), generations);
+    assert_match(&expected, first.name, rest, pattern, position, scenario);
+    expected
+}
+
+/// Check the fixed boards all behave like an unbounded board cut off at their edges
+fn assert_clipped(
+    bounds: Rectangle,
+    boards: &[&BoardInfo],
+    pattern: &Pattern,
+    at: LifePoint,
+    generations: u64,
+    scenario: &str,
+) {
+    let expected = run_clipped(pattern, at, generations, bounds);
+    assert_match(
+        &expected,
+        "clipped reference",
+        boards,
+        pattern,
+        |_| at,
+        scenario,
+    );
+}
+
+fn all_boards() -> Vec<&'static BoardInfo> {
+    available_boards().iter().collect()
+}
+
+fn unbounded_boards() -> Vec<&'static BoardInfo> {
+    available_boards()
+        .iter()
+        .filter(|info| info.bounds.is_none())
+        .collect()
+}
+
+/// The bounded boards, grouped so that every board in a group has the same bounds
+fn bounded_groups() -> Vec<(Rectangle, Vec<&'static BoardInfo>)> {
+    let mut groups: Vec<(Rectangle, Vec<&'static BoardInfo>)> = vec![];
+    for info in available_boards() {
+        if let Some(bounds) = info.bounds {
+            match groups.iter_mut().find(|(b, _)| *b == bounds) {
+                Some((_, group)) => group.push(info),
+                None => groups.push((bounds, vec![info])),
+            }
+        }
+    }
+    groups
+}
+
+fn vertical_blinker() -> Pattern {
+    Pattern::from_tuples(vec![(0, -1), (0, 0), (0, 1)])
+}
+
+/// A glider heading up and to the left, towards negative coordinates
+fn glider_northwest() -> Pattern {
+    let points = Pattern::GLIDER_SOUTHEAST()
+        .get_points()
+        .iter()
+        .map(|p| LifePoint::new(-p.get_x(), -p.get_y()))
+        .collect();
+    Pattern::new(points)
+}
+
+#[test]
+pub fn still_lifes_agree() {
+    let still_lifes = [
+        Pattern::BLOCK(),
+        Pattern::BEEHIVE(),
+        Pattern::LOAF(),
+        Pattern::BOAT(),
+        Pattern::SHIP(),
+        Pattern::TUB(),
+        Pattern::POND(),
+    ];
+    for (i, pattern) in still_lifes.iter().enumerate() {
+        let history = assert_agree(&all_boards(), pattern, |info| info.center, 3, "still life");
+        assert!(history.iter().all(|g| *g == history[0]), "still life {}", i);
+    }
+}
+
+#[test]
+pub fn oscillators_agree() {
+    let oscillators = [
+        (Pattern::BLINKER(), 2),
+        (Pattern::TOAD(), 2),
+        (Pattern::BEACON(), 2),
+        (Pattern::PULSAR(), 3),
+    ];
+    for (pattern, period) in oscillators.iter() {
+        let history = assert_agree(
+            &all_boards(),
+            pattern,
+            |info| info.center,
+            3 * period,
+            "oscillator",
+        );
+        assert_ne!(history[0], history[1]);
+        assert_eq!(history[0], history[*period as usize]);
+        assert_eq!(history[0], history[3 * *period as usize]);
+    }
+}
+
+#[test]
+pub fn spaceships_agree() {
+    let history = assert_agree(
+        &all_boards(),
+        &Pattern::GLIDER_SOUTHEAST(),
+        |info| info.center,
+        100,
+        "glider",
+    );
+    assert_eq!(history[0].translated(25, 25), history[100]);
+}
+
+#[test]
+pub fn methuselah_agrees() {
+    //The acorn stays well inside the fixed boards for this long
+    let history = assert_agree(
+        &all_boards(),
+        &Pattern::ACORN(),
+        |info| info.center,
+        300,
+        "acorn",
+    );
+    assert!(history[300].population() > 100);
+}
+
+#[test]
+pub fn soups_agree() {
+    for i in 0..4 {
+        let soup = generate_soup(&format!("conformance_{}", i), 16, 0.5, Symmetry::C1);
+        assert_agree(&all_boards(), &soup, |info| info.center, 100, "soup");
+    }
+}
+
+#[test]
+pub fn empty_board_agrees() {
+    let history = assert_agree(
+        &all_boards(),
+        &Pattern::new(vec![]),
+        |info| info.center,
+        3,
+        "empty",
+    );
+    assert!(history.iter().all(BoardSnapshot::is_empty));
+}
+
+#[test]
+pub fn region_queries_work() {
+    for info in available_boards() {
+        let at = info.center;
+        let mut board = info.create();
+        for (x, y) in [(1, 1), (2, 3), (5, 1)] {
+            board.set_live_point(&at.offset(x, y));
+        }
+
+        let region = Rectangle::new(at.get_x(), at.get_y(), 3, 4);
+        assert_eq!(
+            vec![at.offset(1, 1), at.offset(2, 3)],
+            *BoardSnapshot::of_cells(board.get_live_cells_in(&region)).get_cells(),
+            "{}",
+            info.name
+        );
+        assert_eq!(2, board.count_live_cells_in(&region), "{}", info.name);
+        let between = Rectangle::new(at.get_x() + 3, at.get_y() + 2, 2, 2);
+        assert_eq!(0, board.count_live_cells_in(&between), "{}", info.name);
+        assert_eq!(
+            0,
+            board.count_live_cells_in(&Rectangle::empty()),
+            "{}",
+            info.name
+        );
+    }
+}
+
+#[test]
+pub fn neighbor_counts_work() {
+    for info in available_boards() {
+        //Counted from a corner of the fixed boards, where some neighbors are off the board
+        let at = info.bounds.map_or(info.center, |b| {
+            LifePoint::new(b.get_x_min(), b.get_y_min())
+        });
+        let mut board = info.create();
+        assert_eq!(
+            0,
+            board.count_live_neighbors(at.get_x(), at.get_y()),
+            "{}",
+            info.name
+        );
+        board.set_live_point(&at);
+        board.set_live_point(&at.offset(0, 1));
+        assert_eq!(
+            1,
+            board.count_live_neighbors(at.get_x(), at.get_y()),
+            "{}",
+            info.name
+        );
+
+        //A cell doesn't count itself
+        board.fill_region(
+            &Rectangle::new(at.get_x(), at.get_y(), 3, 3),
+            &Pattern::from_tuples(vec![(0, 0)]),
+        );
+        let middle = at.offset(1, 1);
+        assert_eq!(
+            8,
+            board.count_live_neighbors(middle.get_x(), middle.get_y()),
+            "{}",
+            info.name
+        );
+    }
+}
+
+#[test]
+pub fn patterns_across_the_origin_agree() {
+    let boards = unbounded_boards();
+    assert_agree(
+        &boards,
+        &Pattern::PULSAR(),
+        |_| LifePoint::ORIGIN,
+        6,
+        "pulsar at origin",
+    );
+    let history = assert_agree(
+        &boards,
+        &glider_northwest(),
+        |_| LifePoint::new(2, 2),
+        40,
+        "glider into negative coordinates",
+    );
+    assert_eq!(history[0].translated(-10, -10), history[40]);
+}
+
+#[test]
+pub fn far_away_patterns_agree() {
+    let boards = unbounded_boards();
+    for at in [
+        LifePoint::new(-1_000_000, -1_000_000),
+        LifePoint::new(1_000_000, -1_000_000),
+        LifePoint::new(-1_000_000, 1_000_000),
+        LifePoint::new(i32::MAX as i64, i32::MIN as i64),
+    ] {
+        assert_agree(&boards, &Pattern::ACORN(), |_| at, 50, "far away acorn");
+        assert_agree(&boards, &glider_northwest(), |_| at, 20, "far away glider");
+    }
+}
+
+#[test]
+pub fn fixed_board_edges_agree() {
+    for (bounds, boards) in bounded_groups() {
+        let corners = [
+            LifePoint::new(bounds.get_x_min(), bounds.get_y_min()),
+            LifePoint::new(bounds.get_x_max(), bounds.get_y_min()),
+            LifePoint::new(bounds.get_x_min(), bounds.get_y_max()),
+            LifePoint::new(bounds.get_x_max(), bounds.get_y_max()),
+        ];
+        //A block squashed into each corner, and a blinker lying along each edge
+        for corner in corners {
+            let block_at = LifePoint::new(
+                corner.get_x().min(bounds.get_x_max() - 1),
+                corner.get_y().min(bounds.get_y_max() - 1),
+            );
+            assert_clipped(
+                bounds,
+                &boards,
+                &Pattern::BLOCK(),
+                block_at,
+                3,
+                "corner block",
+            );
+        }
+        let middle_y = bounds.get_y_min() + bounds.get_height() / 2;
+        let middle_x = bounds.get_x_min() + bounds.get_width() / 2;
+        for at in [
+            LifePoint::new(bounds.get_x_min(), middle_y),
+            LifePoint::new(bounds.get_x_max(), middle_y),
+        ] {
+            assert_clipped(bounds, &boards, &vertical_blinker(), at, 4, "edge blinker");
+        }
+        for at in [
+            LifePoint::new(middle_x, bounds.get_y_min()),
+            LifePoint::new(middle_x, bounds.get_y_max()),
+        ] {
+            assert_clipped(bounds, &boards, &Pattern::BLINKER(), at, 4, "edge blinker");
+        }
+
+        //Gliders crashing into the far corner and the near corner
+        let far = LifePoint::new(bounds.get_x_max() - 10, bounds.get_y_max() - 10);
+        assert_clipped(
+            bounds,
+            &boards,
+            &Pattern::GLIDER_SOUTHEAST(),
+            far,
+            80,
+            "glider into far corner",
+        );
+        let near = LifePoint::new(bounds.get_x_min() + 10, bounds.get_y_min() + 10);
+        assert_clipped(
+            bounds,
+            &boards,
+            &glider_northwest(),
+            near,
+            80,
+            "glider into near corner",
+        );
+    }
+}
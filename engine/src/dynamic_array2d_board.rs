@@ -1,8 +1,7 @@
+use super::error::EngineError;
 use super::life_board::LifeBoard;
-use std::cmp::max;
-use std::cmp::min;
-use std::fmt::Display;
-use std::ops::Range;
+use super::life_point::LifePoint;
+use super::rectangle::Rectangle;
 
 pub struct DynamicArray2dLifeBoard {
     grid: ArrayGrid,
@@ -48,10 +47,10 @@ impl LifeBoard for DynamicArray2dLifeBoard {
     /// Count the live neighbors of this cell, not counting the cell itself
     fn count_live_neighbors(&self, x: BoardIndex, y: BoardIndex) -> u8 {
         let (xu, yu) = self.board_extent.to_grid_point(x, y);
-        let is_on_edge = xu <= 0
-            || yu <= 0
-            || xu >= (self.board_extent.width as usize - 1)
-            || yu >= (self.board_extent.height as usize - 1);
+        let is_on_edge = xu == 0
+            || yu == 0
+            || xu >= (self.board_extent.get_width() as usize).saturating_sub(1)
+            || yu >= (self.board_extent.get_height() as usize).saturating_sub(1);
 
         if is_on_edge {
             self.is_live_num(x - 1, y - 1)
@@ -77,12 +76,12 @@ impl LifeBoard for DynamicArray2dLifeBoard {
     fn set_liveness(&mut self, x: BoardIndex, y: BoardIndex, is_live: bool) {
         //Check if we have space in the current grid, and if not expand it
         if !self.board_extent.contains_point(x, y) {
-            let mut new_board_extent = self.board_extent.clone();
+            let mut new_board_extent = self.board_extent;
             new_board_extent.expand_to_include(x, y);
 
             let mut new_grid = ArrayGrid::create(
-                new_board_extent.width as usize,
-                new_board_extent.height as usize,
+                new_board_extent.get_width() as usize,
+                new_board_extent.get_height() as usize,
             );
             //Copy old grid values to new grid
             for xi in self.live_extent.x_range() {
@@ -104,6 +103,17 @@ impl LifeBoard for DynamicArray2dLifeBoard {
         let live: u8 = if is_live { 1 } else { 0 };
         self.grid.set(xu, yu, live);
     }
+
+    /// Dynamic boards grow to fit any cell, so this never fails
+    fn try_set_liveness(
+        &mut self,
+        x: BoardIndex,
+        y: BoardIndex,
+        is_live: bool,
+    ) -> Result<(), EngineError> {
+        self.set_liveness(x, y, is_live);
+        Ok(())
+    }
     fn is_live(&self, x: BoardIndex, y: BoardIndex) -> bool {
         self.live_extent.contains_point(x, y)
             && self.is_live_unchecked(
@@ -118,16 +128,16 @@ impl LifeBoard for DynamicArray2dLifeBoard {
         }
         //We'll make the new board one larger than the existing live_extent in every direction so we can't possibly grow off the sides
         //This does not grow unbounded because we're basing off live_extent, not board_extent
-        let new_board_extent = Rectangle {
-            x_min: self.live_extent.x_min - 1,
-            width: self.live_extent.width + 2,
-            y_min: self.live_extent.y_min - 1,
-            height: self.live_extent.height + 2,
-        };
+        let new_board_extent = Rectangle::new(
+            self.live_extent.get_x_min() - 1,
+            self.live_extent.get_y_min() - 1,
+            self.live_extent.get_width() + 2,
+            self.live_extent.get_height() + 2,
+        );
 
         let mut new_grid = ArrayGrid::create(
-            new_board_extent.width as usize,
-            new_board_extent.height as usize,
+            new_board_extent.get_width() as usize,
+            new_board_extent.get_height() as usize,
         );
 
         let mut new_live_extent = Rectangle::empty();
@@ -156,91 +166,31 @@ impl LifeBoard for DynamicArray2dLifeBoard {
             ("live_extent", format!("{}", &self.live_extent)),
         ]
     }
-}
 
-type BoardIndex = i64;
-type GridIndex = usize;
-
-#[derive(Copy, Clone, Debug)]
-struct Rectangle {
-    x_min: BoardIndex,
-    y_min: BoardIndex,
-    width: BoardIndex,
-    height: BoardIndex,
-}
-
-impl Rectangle {
-    fn contains_point(&self, x: BoardIndex, y: BoardIndex) -> bool {
-        return x >= self.x_min && x <= self.x_max() && y >= self.y_min && y <= self.y_max();
+    fn get_live_cells(&self) -> Vec<LifePoint> {
+        self.get_live_cells_in(&self.live_extent)
     }
 
-    fn expand_to_include(&mut self, x: BoardIndex, y: BoardIndex) {
-        if self.is_empty() {
-            self.x_min = x;
-            self.y_min = y;
-            self.width = 1;
-            self.height = 1;
-        } else {
-            let x_max = max(self.x_min + self.width - 1, x);
-            let y_max = max(self.y_min + self.height - 1, y);
-            self.x_min = min(self.x_min, x);
-            self.width = x_max - self.x_min + 1;
-            self.y_min = min(self.y_min, y);
-            self.height = y_max - self.y_min + 1;
-        }
+    fn get_bounds(&self) -> Option<Rectangle> {
+        None
     }
 
-    fn empty() -> Rectangle {
-        Rectangle {
-            x_min: 0,
-            width: 0,
-            y_min: 0,
-            height: 0,
+    fn get_live_cells_in(&self, region: &Rectangle) -> Vec<LifePoint> {
+        //Only the part of the region that overlaps the live cells needs checking
+        let search_area = self.live_extent.intersection(region);
+        let mut cells = vec![];
+        for xi in search_area.x_range() {
+            for yi in search_area.y_range() {
+                if self.is_live(xi, yi) {
+                    cells.push(LifePoint::new(xi, yi));
+                }
+            }
         }
-    }
-
-    fn x_max(&self) -> BoardIndex {
-        self.x_min + self.width - 1
-    }
-
-    fn y_max(&self) -> BoardIndex {
-        self.y_min + self.height - 1
-    }
-
-    fn is_empty(&self) -> bool {
-        self.width == 0 && self.height == 0
-    }
-
-    fn to_grid_x(&self, x: BoardIndex) -> GridIndex {
-        (x - self.x_min) as GridIndex
-    }
-
-    fn to_grid_y(&self, y: BoardIndex) -> GridIndex {
-        (y - self.y_min) as GridIndex
-    }
-
-    fn to_grid_point(&self, x: BoardIndex, y: BoardIndex) -> (GridIndex, GridIndex) {
-        (self.to_grid_x(x), self.to_grid_y(y))
-    }
-
-    fn x_range(&self) -> Range<BoardIndex> {
-        self.x_min..(self.x_min + self.width)
-    }
-
-    fn y_range(&self) -> Range<BoardIndex> {
-        self.y_min..(self.y_min + self.height)
+        cells
     }
 }
 
-impl Display for Rectangle {
-    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
-        write!(
-            fmt,
-            "(minX:{}, minY:{}, width:{}, height:{})",
-            self.x_min, self.y_min, self.width, self.height
-        )
-    }
-}
+type BoardIndex = i64;
 
 struct ArrayGrid {
     col_size: usize,
@@ -262,7 +212,7 @@ impl ArrayGrid {
         yu + xu * self.col_size
     }
 
-    fn iter(&self) -> std::slice::Iter<u8> {
+    fn iter(&self) -> std::slice::Iter<'_, u8> {
         self.arr.iter()
     }
 
@@ -275,8 +225,7 @@ impl ArrayGrid {
 
     fn create(row_size: usize, col_size: usize) -> ArrayGrid {
         let size = row_size * col_size;
-        let mut arr: Vec<u8> = Vec::with_capacity(size);
-        arr.resize(size, 0);
+        let arr: Vec<u8> = vec![0; size];
 
         ArrayGrid { col_size, arr }
     }
@@ -286,46 +235,13 @@ impl ArrayGrid {
 mod test {
     use super::*;
 
-    #[test]
-    pub fn step_one_works_empty() {
-        let mut board = DynamicArray2dLifeBoard::empty();
-        board.step_one();
-        assert_eq!(0, board.get_live_count());
-    }
-
-    #[test]
-    pub fn step_one_works_blinker() {
-        let mut board = DynamicArray2dLifeBoard::empty();
-        board.set_live(2, 2);
-        board.set_live(2, 3);
-        board.set_live(2, 4);
-        board.set_live(5, 5);
-        board.step_one();
-        assert_eq!(3, board.get_live_count());
-        assert_eq!(true, board.is_live(1, 3));
-        assert_eq!(true, board.is_live(2, 3));
-        assert_eq!(true, board.is_live(3, 3));
-        assert_eq!(false, board.is_live(2, 2));
-        assert_eq!(false, board.is_live(2, 4));
-        assert_eq!(false, board.is_live(5, 5));
-
-        board.step_one();
-        assert_eq!(3, board.get_live_count());
-        assert_eq!(true, board.is_live(2, 2));
-        assert_eq!(true, board.is_live(2, 3));
-        assert_eq!(true, board.is_live(2, 4));
-        assert_eq!(false, board.is_live(1, 3));
-        assert_eq!(false, board.is_live(3, 3));
-        assert_eq!(false, board.is_live(5, 5));
-    }
-
     #[test]
     pub fn set_live_minus() {
         let mut board = DynamicArray2dLifeBoard::empty();
         board.set_live(5, 5);
         board.set_live(4, 5);
-        assert_eq!(true, board.is_live(5, 5));
-        assert_eq!(true, board.is_live(4, 5));
+        assert!(board.is_live(5, 5));
+        assert!(board.is_live(4, 5));
     }
 
     #[test]
@@ -340,75 +256,50 @@ mod test {
         board.set_live(0, 0);
         board.set_live(0, 1);
 
-        assert_eq!(true, board.is_live(0, 0));
-        assert_eq!(true, board.is_live(0, 1));
-        assert_eq!(1, board.live_extent.width);
-        assert_eq!(2, board.live_extent.height);
+        assert!(board.is_live(0, 0));
+        assert!(board.is_live(0, 1));
+        assert_eq!(1, board.live_extent.get_width());
+        assert_eq!(2, board.live_extent.get_height());
         assert_eq!(2, board.get_live_count());
 
         board.set_live(1, 2);
-        assert_eq!(2, board.live_extent.width);
-        assert_eq!(3, board.live_extent.height);
-        assert_eq!(true, board.is_live(1, 2));
+        assert_eq!(2, board.live_extent.get_width());
+        assert_eq!(3, board.live_extent.get_height());
+        assert!(board.is_live(1, 2));
         assert_eq!(3, board.get_live_count());
 
         board.set_live(4, 5);
-        assert_eq!(5, board.live_extent.width);
-        assert_eq!(6, board.live_extent.height);
-        assert_eq!(true, board.is_live(4, 5));
+        assert_eq!(5, board.live_extent.get_width());
+        assert_eq!(6, board.live_extent.get_height());
+        assert!(board.is_live(4, 5));
         assert_eq!(4, board.get_live_count());
     }
 
     #[test]
     pub fn set_live_ensures_capacity_in_negative_coordinates() {
         let mut board = DynamicArray2dLifeBoard::empty();
-        assert_eq!(false, board.is_live(0, 0));
-        assert_eq!(0, board.live_extent.width);
-        assert_eq!(0, board.live_extent.height);
+        assert!(!board.is_live(0, 0));
+        assert_eq!(0, board.live_extent.get_width());
+        assert_eq!(0, board.live_extent.get_height());
         board.set_live(10, 10);
-        assert_eq!(true, board.is_live(10, 10));
-        assert_eq!(1, board.live_extent.width);
-        assert_eq!(1, board.live_extent.height);
+        assert!(board.is_live(10, 10));
+        assert_eq!(1, board.live_extent.get_width());
+        assert_eq!(1, board.live_extent.get_height());
 
         board.set_live(5, 5);
-        assert_eq!(true, board.is_live(5, 5));
-        assert_eq!(true, board.is_live(10, 10));
-        assert_eq!(6, board.live_extent.width);
-        assert_eq!(6, board.live_extent.height);
+        assert!(board.is_live(5, 5));
+        assert!(board.is_live(10, 10));
+        assert_eq!(6, board.live_extent.get_width());
+        assert_eq!(6, board.live_extent.get_height());
     }
 
     #[test]
     pub fn live_extent_works_off_origin() {
         let mut board = DynamicArray2dLifeBoard::empty();
         board.set_live(90, 100);
-        assert_eq!(1, board.live_extent.width);
-        assert_eq!(1, board.live_extent.height);
-        assert_eq!(90, board.live_extent.x_min);
-        assert_eq!(100, board.live_extent.y_min);
-    }
-
-    #[test]
-    pub fn count_live_neighbors_works_at_borders() {
-        let mut board = DynamicArray2dLifeBoard::empty();
-        board.set_live(0, 0);
-        board.set_live(0, 1);
-
-        let neighbors = board.count_live_neighbors(0, 0);
-        assert_eq!(neighbors, 1);
-    }
-
-    #[test]
-    pub fn count_live_neighbors_doesnt_count_self() {
-        let mut board = DynamicArray2dLifeBoard::empty();
-        for xi in 0..3 {
-            for yi in 0..3 {
-                board.set_live(xi, yi);
-            }
-        }
-
-        assert_eq!(9, board.get_live_count());
-
-        let neighbors = board.count_live_neighbors(1, 1);
-        assert_eq!(neighbors, 8);
+        assert_eq!(1, board.live_extent.get_width());
+        assert_eq!(1, board.live_extent.get_height());
+        assert_eq!(90, board.live_extent.get_x_min());
+        assert_eq!(100, board.live_extent.get_y_min());
     }
 }
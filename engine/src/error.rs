@@ -0,0 +1,151 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+/// The most pixels an image can have, to stop a huge region from using up all the memory
+pub const MAX_IMAGE_PIXELS: u64 = 1 << 28;
+
+/// Everything that can go wrong when handing the engine bad input
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EngineError {
+    /// The cell is outside a fixed-size board, which is `width` by `height` cells starting at the origin
+    OutOfBounds {
+        x: i64,
+        y: i64,
+        width: i64,
+        height: i64,
+    },
+    /// A row of a grid doesn't have the same number of cells as the first row
+    RaggedGrid {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+    /// A row of a grid doesn't have room for the same number of cells as the first row
+    UnevenCapacity {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+    /// The apgcode prefix isn't `xs`, `xp` or `xq` followed by a number
+    UnsupportedApgcodePrefix(String),
+    /// The apgcode body isn't valid extended Wechsler format
+    InvalidApgcode {
+        code: String,
+        reason: String,
+    },
+    UnknownSymmetry(String),
+    /// There's no board implementation with this name in the registry
+    UnknownBoard(String),
+    /// A rectangle has a negative size, or its far edges are past the largest coordinate
+    InvalidRectangle {
+        x_min: i64,
+        y_min: i64,
+        width: i64,
+        height: i64,
+    },
+    /// A rendered image would have no pixels, more than `MAX_IMAGE_PIXELS`, or be too big for its file format
+    InvalidImageSize {
+        width: u64,
+        height: u64,
+    },
+}
+
+impl fmt::Display for EngineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EngineError::OutOfBounds {
+                x,
+                y,
+                width,
+                height,
+            } => write!(
+                f,
+                "Cell ({}, {}) is outside the {}x{} board",
+                x, y, width, height
+            ),
+            EngineError::RaggedGrid {
+                row,
+                expected,
+                found,
+            } => write!(
+                f,
+                "Row {} of the grid has {} cells, but the first row has {}",
+                row, found, expected
+            ),
+            EngineError::UnevenCapacity {
+                row,
+                expected,
+                found,
+            } => write!(
+                f,
+                "Row {} of the grid has room for {} cells, but the first row has room for {}",
+                row, found, expected
+            ),
+            EngineError::UnsupportedApgcodePrefix(prefix) => {
+                write!(f, "Unsupported apgcode prefix '{}'", prefix)
+            }
+            EngineError::InvalidApgcode { code, reason } => {
+                write!(f, "{} in '{}'", reason, code)
+            }
+            EngineError::UnknownSymmetry(symmetry) => write!(f, "Unknown symmetry '{}'", symmetry),
+            EngineError::UnknownBoard(name) => write!(f, "Unknown board implementation '{}'", name),
+            EngineError::InvalidRectangle {
+                x_min,
+                y_min,
+                width,
+                height,
+            } => write!(
+                f,
+                "A {}x{} rectangle at ({}, {}) isn't valid",
+                width, height, x_min, y_min
+            ),
+            EngineError::InvalidImageSize { width, height } => write!(
+                f,
+                "Can't render a {}x{} pixel image, it needs between 1 and {} pixels and has to fit the file format",
+                width, height, MAX_IMAGE_PIXELS
+            ),
+        }
+    }
+}
+
+impl Error for EngineError {}
+
+/// Lets the engine's errors be returned from the functions that write files
+impl From<EngineError> for io::Error {
+    fn from(error: EngineError) -> io::Error {
+        io::Error::new(io::ErrorKind::InvalidInput, error)
+    }
+}
+
+/// Check that a cell is on a fixed-size board starting at the origin
+pub(crate) fn check_bounds(x: i64, y: i64, width: i64, height: i64) -> Result<(), EngineError> {
+    if x < 0 || y < 0 || x >= width || y >= height {
+        Err(EngineError::OutOfBounds {
+            x,
+            y,
+            width,
+            height,
+        })
+    } else {
+        Ok(())
+    }
+}
+
+/// Check that an image of `width` by `height` pixels has at least one pixel and no more than `MAX_IMAGE_PIXELS`.
+/// The sides are taken as `i128` so callers can work them out without overflowing.
+pub(crate) fn check_image_size(width: i128, height: i128) -> Result<(u64, u64), EngineError> {
+    let fits = width > 0
+        && height > 0
+        && width
+            .checked_mul(height)
+            .is_some_and(|pixels| pixels <= MAX_IMAGE_PIXELS as i128);
+    if fits {
+        Ok((width as u64, height as u64))
+    } else {
+        Err(EngineError::InvalidImageSize {
+            width: u64::try_from(width).unwrap_or(u64::MAX),
+            height: u64::try_from(height).unwrap_or(u64::MAX),
+        })
+    }
+}
@@ -1,25 +1,46 @@
 use crate::array_grid::ArrayGrid;
+use crate::error::{check_bounds, EngineError};
 use crate::life_board::LifeBoard;
 use crate::life_cell::LifeCell;
+use crate::life_point::LifePoint;
+use crate::rectangle::Rectangle;
 
-const GRID_SIZE: usize = 258;
-const BOARD_SIZE: i64 = GRID_SIZE as i64 - 2;
+/// Width and height of the board made by `empty`
+const DEFAULT_SIZE: usize = 256;
 
 /// A fixed-size `LifeBoard` where each cell is represented by a bitfield.
+/// The grid has a ring of always-dead cells around the board, so cells on the edge of the board can update
+/// their neighbors' counts without any special cases.
 pub struct FixedBitfieldLifeBoard {
     grid: ArrayGrid<LifeCell>,
+    width: i64,
+    height: i64,
 }
 
 impl FixedBitfieldLifeBoard {
     pub fn empty() -> FixedBitfieldLifeBoard {
-        let grid: ArrayGrid<LifeCell> = ArrayGrid::create(GRID_SIZE, GRID_SIZE);
-        FixedBitfieldLifeBoard { grid: grid }
+        FixedBitfieldLifeBoard::with_size(DEFAULT_SIZE, DEFAULT_SIZE)
+    }
+
+    /// A board of `width` by `height` cells, starting at the origin
+    pub fn with_size(width: usize, height: usize) -> FixedBitfieldLifeBoard {
+        let grid: ArrayGrid<LifeCell> = ArrayGrid::create(width + 2, height + 2);
+        FixedBitfieldLifeBoard {
+            grid,
+            width: width as i64,
+            height: height as i64,
+        }
     }
 
     fn convert_coordinates(&self, x: i64, y: i64) -> (usize, usize) {
         ((x + 1) as usize, (y + 1) as usize)
     }
 
+    /// Whether the cell is on the board or in the dead zone around its edge, so that it's safe to look up
+    fn is_in_grid(&self, x: i64, y: i64) -> bool {
+        (-1..=self.width).contains(&x) && (-1..=self.height).contains(&y)
+    }
+
     fn get_live_count(&self) -> u64 {
         self.grid
             .iter()
@@ -31,12 +52,26 @@ impl FixedBitfieldLifeBoard {
 impl LifeBoard for FixedBitfieldLifeBoard {
     /// Count the live neighbors of this cell, not counting the cell itself
     fn count_live_neighbors(&self, x: i64, y: i64) -> u8 {
+        if !self.is_in_grid(x, y) {
+            return 0;
+        }
         let (xu, yu) = self.convert_coordinates(x, y);
         self.grid.get(xu, yu).neighbors_count()
     }
 
     fn set_liveness(&mut self, x: i64, y: i64, is_live: bool) {
+        if let Err(e) = self.try_set_liveness(x, y, is_live) {
+            panic!("{}", e);
+        }
+    }
+
+    fn try_set_liveness(&mut self, x: i64, y: i64, is_live: bool) -> Result<(), EngineError> {
+        check_bounds(x, y, self.width, self.height)?;
         let (xu, yu) = self.convert_coordinates(x, y);
+        //The neighbors' counts only change when this cell does, so writing the state it already has is a no-op
+        if self.grid.get(xu, yu).is_live() == is_live {
+            return Ok(());
+        }
         if is_live {
             self.grid.get_mut(xu - 1, yu - 1).increment_neighbors();
             self.grid.get_mut(xu - 1, yu).increment_neighbors();
@@ -58,9 +93,13 @@ impl LifeBoard for FixedBitfieldLifeBoard {
             self.grid.get_mut(xu + 1, yu).decrement_neighbors();
             self.grid.get_mut(xu + 1, yu + 1).decrement_neighbors();
         };
+        Ok(())
     }
 
     fn is_live(&self, x: i64, y: i64) -> bool {
+        if !self.is_in_grid(x, y) {
+            return false;
+        }
         let (xu, yu) = self.convert_coordinates(x, y);
         self.grid.get(xu, yu).is_live()
     }
@@ -69,8 +108,8 @@ impl LifeBoard for FixedBitfieldLifeBoard {
         //Duplicate the internal vectors so that we don't lose the prior state halfway through
         let old_state = self.grid.clone();
 
-        for xi in 0..BOARD_SIZE {
-            for yi in 0..BOARD_SIZE {
+        for xi in 0..self.width {
+            for yi in 0..self.height {
                 let (xu, yu) = self.convert_coordinates(xi, yi);
                 let old_cell = old_state.get(xu, yu);
                 if old_cell.is_deadzone() {
@@ -93,80 +132,198 @@ impl LifeBoard for FixedBitfieldLifeBoard {
         vec![
             ("implementation", "Fixed bitfield".to_owned()),
             ("live_cells", self.get_live_count().to_string()),
-            ("grid_size", GRID_SIZE.to_string()),
-            ("board_size", (GRID_SIZE - 2).to_string()),
+            ("width", self.width.to_string()),
+            ("height", self.height.to_string()),
         ]
     }
+
+    fn get_live_cells(&self) -> Vec<LifePoint> {
+        let mut cells = vec![];
+        for xi in 0..self.width {
+            for yi in 0..self.height {
+                if self.is_live(xi, yi) {
+                    cells.push(LifePoint::new(xi, yi));
+                }
+            }
+        }
+        cells
+    }
+
+    fn get_bounds(&self) -> Option<Rectangle> {
+        Some(Rectangle::new(0, 0, self.width, self.height))
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::pattern::Pattern;
+
+    #[test]
+    pub fn can_create_empty_board() {
+        let board = FixedBitfieldLifeBoard::empty();
+        assert_eq!(0, board.get_live_count());
+        assert!(!board.is_live(0, 0));
+        assert!(!board.is_live(0, 1));
+        assert!(!board.is_live(1, 1));
+    }
 
     #[test]
-    pub fn step_one_works_empty() {
+    pub fn set_liveness_off_the_board_is_an_error() {
+        const BOARD_SIZE: i64 = DEFAULT_SIZE as i64;
         let mut board = FixedBitfieldLifeBoard::empty();
-        board.step_one();
+        for (x, y) in [
+            (-1, 0),
+            (0, -1),
+            (BOARD_SIZE, 0),
+            (0, BOARD_SIZE),
+            (i64::MIN, i64::MAX),
+        ] {
+            assert_eq!(
+                Err(EngineError::OutOfBounds {
+                    x,
+                    y,
+                    width: BOARD_SIZE,
+                    height: BOARD_SIZE
+                }),
+                board.try_set_liveness(x, y, true)
+            );
+        }
         assert_eq!(0, board.get_live_count());
+
+        assert_eq!(Ok(()), board.try_set_liveness(BOARD_SIZE - 1, 0, true));
+        assert!(board.is_live(BOARD_SIZE - 1, 0));
     }
 
     #[test]
-    pub fn step_one_works_blinker() {
+    pub fn reading_off_the_board_is_dead() {
         let mut board = FixedBitfieldLifeBoard::empty();
-        board.set_live(2, 2);
-        board.set_live(2, 3);
-        board.set_live(2, 4);
-        board.set_live(5, 5);
+        board.set_live(0, 0);
+        assert!(!board.is_live(-5, 1000));
+        assert_eq!(1, board.count_live_neighbors(-1, -1));
+        assert_eq!(0, board.count_live_neighbors(-2, 0));
+        assert_eq!(0, board.count_live_neighbors(i64::MAX, 0));
+    }
+
+    #[test]
+    pub fn any_size_works() {
+        for (width, height) in [(1, 1), (3, 1), (5, 7), (300, 20)] {
+            let mut board = FixedBitfieldLifeBoard::with_size(width, height);
+            let (w, h) = (width as i64, height as i64);
+            assert!(board.try_set_liveness(w - 1, h - 1, true).is_ok());
+            assert!(board.try_set_liveness(w, 0, true).is_err());
+            assert!(board.try_set_liveness(0, h, true).is_err());
+            board.set_liveness(w - 1, h - 1, false);
+
+            //Fill the board completely, then check every count against its neighbors
+            for x in 0..w {
+                for y in 0..h {
+                    board.set_live(x, y);
+                }
+            }
+            for x in -1..=w {
+                for y in -1..=h {
+                    let expected = LifePoint::new(x, y)
+                        .neighbors()
+                        .filter(|n| board.is_live_point(n))
+                        .count() as u8;
+                    assert_eq!(expected, board.count_live_neighbors(x, y), "({}, {})", x, y);
+                }
+            }
+            assert_eq!((width * height) as u64, board.get_live_count());
+        }
+    }
+
+    #[test]
+    pub fn blinker_on_a_tiny_board() {
+        //A 3x3 board holds a blinker exactly, and its edges don't affect it
+        let mut board = FixedBitfieldLifeBoard::with_size(3, 3);
+        board.set_live(1, 0);
+        board.set_live(1, 1);
+        board.set_live(1, 2);
         board.step_one();
+        assert!(board.is_live(0, 1) && board.is_live(1, 1) && board.is_live(2, 1));
         assert_eq!(3, board.get_live_count());
-        assert_eq!(true, board.is_live(1, 3));
-        assert_eq!(true, board.is_live(2, 3));
-        assert_eq!(true, board.is_live(3, 3));
-        assert_eq!(false, board.is_live(2, 2));
-        assert_eq!(false, board.is_live(2, 4));
-        assert_eq!(false, board.is_live(5, 5));
-
         board.step_one();
+        assert!(board.is_live(1, 0) && board.is_live(1, 2));
         assert_eq!(3, board.get_live_count());
-        assert_eq!(true, board.is_live(2, 2));
-        assert_eq!(true, board.is_live(2, 3));
-        assert_eq!(true, board.is_live(2, 4));
-        assert_eq!(false, board.is_live(1, 3));
-        assert_eq!(false, board.is_live(3, 3));
-        assert_eq!(false, board.is_live(5, 5));
     }
 
     #[test]
-    pub fn can_create_empty_board() {
-        let board = FixedBitfieldLifeBoard::empty();
-        assert_eq!(0, board.get_live_count());
-        assert_eq!(false, board.is_live(0, 0));
-        assert_eq!(false, board.is_live(0, 1));
-        assert_eq!(false, board.is_live(1, 1));
+    pub fn cells_dont_grow_past_the_edge() {
+        //A blinker on the edge would need cells off the board, so it dies down to a single column
+        let mut board = FixedBitfieldLifeBoard::with_size(2, 3);
+        board.set_live(0, 0);
+        board.set_live(0, 1);
+        board.set_live(0, 2);
+        board.step_one();
+        let mut cells = board.get_live_cells();
+        cells.sort();
+        assert_eq!(vec![LifePoint::new(0, 1), LifePoint::new(1, 1)], cells);
     }
 
     #[test]
-    pub fn count_live_neighbors_works_at_borders() {
+    pub fn setting_a_cell_to_its_own_state_keeps_counts() {
         let mut board = FixedBitfieldLifeBoard::empty();
-        board.set_live(0, 0);
-        board.set_live(0, 1);
-
-        let neighbors = board.count_live_neighbors(0, 0);
-        assert_eq!(neighbors, 1);
+        board.set_live(5, 5);
+        board.set_live(5, 5);
+        assert_eq!(1, board.count_live_neighbors(4, 4));
+        board.set_liveness(6, 6, false);
+        assert_eq!(1, board.count_live_neighbors(5, 6));
+        assert_eq!(0, board.count_live_neighbors(5, 5));
     }
 
     #[test]
-    pub fn count_live_neighbors_doesnt_count_self() {
+    pub fn overlapping_patterns_keep_counts() {
         let mut board = FixedBitfieldLifeBoard::empty();
-        for xi in 0..3 {
-            for yi in 0..3 {
-                board.set_live(xi, yi);
+        //Every block shares cells with the ones next to it, and the last is drawn over the first
+        for offset in [0, 1, 2, 1, 0] {
+            board.draw_pattern(&Pattern::BLOCK(), &LifePoint::new(10 + offset, 10));
+        }
+        board.draw_pattern(&Pattern::BLINKER(), &LifePoint::new(11, 10));
+
+        let live = board.get_live_cells();
+        assert_eq!(8, live.len());
+        for x in 8..16 {
+            for y in 8..14 {
+                let expected = LifePoint::new(x, y)
+                    .neighbors()
+                    .filter(|n| live.contains(n))
+                    .count() as u8;
+                assert_eq!(expected, board.count_live_neighbors(x, y), "({}, {})", x, y);
             }
         }
 
-        assert_eq!(9, board.get_live_count());
+        //Counts that were only ever changed by real births and deaths leave the board empty once everything's gone
+        for cell in live {
+            board.set_liveness_point(&cell, false);
+            board.set_liveness_point(&cell, false);
+        }
+        assert!(board.grid.iter().all(LifeCell::is_deadzone));
+    }
 
-        let neighbors = board.count_live_neighbors(1, 1);
-        assert_eq!(neighbors, 8);
+    #[test]
+    pub fn full_neighborhoods_dont_overflow() {
+        let mut board = FixedBitfieldLifeBoard::empty();
+        for _ in 0..3 {
+            board.draw_pattern(
+                &Pattern::from_tuples(vec![
+                    (0, 0),
+                    (1, 0),
+                    (2, 0),
+                    (0, 1),
+                    (1, 1),
+                    (2, 1),
+                    (0, 2),
+                    (1, 2),
+                    (2, 2),
+                ]),
+                &LifePoint::new(20, 20),
+            );
+        }
+        assert_eq!(8, board.count_live_neighbors(21, 21));
+        board.step_one();
+        //Only the corners of a full 3x3 square survive, plus the births beside each edge
+        assert_eq!(8, board.get_live_count());
     }
 }
@@ -1,4 +1,7 @@
+use crate::error::{check_bounds, EngineError};
 use crate::life_board::LifeBoard;
+use crate::life_point::LifePoint;
+use crate::rectangle::Rectangle;
 use std::convert::From;
 
 /// A `LifeBoard` backed by a fixed-size `Vector`. Any coordinates that fall off the initial board are forced to dead.
@@ -10,14 +13,16 @@ pub struct FixedVectorLifeBoard {
 
 impl FixedVectorLifeBoard {
     pub fn empty() -> FixedVectorLifeBoard {
-        let size = 256;
-        let mut column_vec: Vec<bool> = Vec::with_capacity(size);
-        column_vec.resize(size, false);
-
-        let mut row_vec: Vec<Vec<bool>> = Vec::with_capacity(size);
-        row_vec.resize(size, column_vec);
+        FixedVectorLifeBoard::with_size(256, 256)
+    }
 
-        FixedVectorLifeBoard::from(row_vec)
+    /// A board of `width` by `height` cells, starting at the origin
+    pub fn with_size(width: usize, height: usize) -> FixedVectorLifeBoard {
+        FixedVectorLifeBoard {
+            grid: vec![vec![false; height]; width],
+            x_size: width,
+            y_size: height,
+        }
     }
 
     fn is_live_num(&self, x: i64, y: i64) -> u8 {
@@ -38,7 +43,7 @@ impl FixedVectorLifeBoard {
         for row in &self.grid {
             for cell in row {
                 if *cell {
-                    count = count + 1;
+                    count += 1;
                 }
             }
         }
@@ -82,12 +87,16 @@ impl LifeBoard for FixedVectorLifeBoard {
     }
 
     fn set_liveness(&mut self, x: i64, y: i64, is_live: bool) {
-        if x < 0 || y < 0 || x >= self.x_size as i64 || y >= self.y_size as i64 {
-            panic!("Coordinate is outside of FixedVectorLifeBoard bounds");
+        if let Err(e) = self.try_set_liveness(x, y, is_live) {
+            panic!("{}", e);
         }
+    }
 
+    fn try_set_liveness(&mut self, x: i64, y: i64, is_live: bool) -> Result<(), EngineError> {
+        check_bounds(x, y, self.x_size as i64, self.y_size as i64)?;
         let (xu, yu) = self.convert_coordinates(x, y);
         self.grid.get_mut(xu).unwrap()[yu] = is_live;
+        Ok(())
     }
 
     fn is_live(&self, x: i64, y: i64) -> bool {
@@ -98,7 +107,7 @@ impl LifeBoard for FixedVectorLifeBoard {
             self.grid
                 .get(xu)
                 .and_then(|row| row.get(yu))
-                .map(|b| *b)
+                .copied()
                 .unwrap_or(false)
         }
     }
@@ -127,32 +136,73 @@ impl LifeBoard for FixedVectorLifeBoard {
             ("y_size", self.y_size.to_string()),
         ]
     }
+
+    fn get_live_cells(&self) -> Vec<LifePoint> {
+        let mut cells = vec![];
+        for (xu, row) in self.grid.iter().enumerate() {
+            for (yu, cell) in row.iter().enumerate() {
+                if *cell {
+                    cells.push(LifePoint::new(xu as i64, yu as i64));
+                }
+            }
+        }
+        cells
+    }
+
+    fn get_bounds(&self) -> Option<Rectangle> {
+        Some(Rectangle::new(0, 0, self.x_size as i64, self.y_size as i64))
+    }
 }
 
-/// Create a new `VectorGrid` from the given set of booleans. Each live cell should be indicated with a `true`, dead cells with a `false`.
-/// The board implicitly starts at the origin, ie cell `(0, 0)`.
-/// All of the vectors must be the same length and capacity.
-impl From<Vec<Vec<bool>>> for FixedVectorLifeBoard {
-    fn from(grid: Vec<Vec<bool>>) -> Self {
+impl FixedVectorLifeBoard {
+    /// Create a new board from the given set of booleans, indexed by x then y. Each live cell should be indicated with a `true`,
+    /// dead cells with a `false`. The board implicitly starts at the origin, ie cell `(0, 0)`, and is as big as the vectors' capacity,
+    /// with any spare capacity filled with dead cells.
+    /// Every column must be the same length and capacity, otherwise this returns `EngineError::RaggedGrid` or `EngineError::UnevenCapacity`.
+    pub fn try_from_grid(mut grid: Vec<Vec<bool>>) -> Result<FixedVectorLifeBoard, EngineError> {
         let x_size = grid.capacity();
-        let y_size = grid.get(0).map(|v| v.capacity()).unwrap_or(0);
+        let y_size = grid.first().map(|v| v.capacity()).unwrap_or(0);
 
-        if grid.len() > 1 {
-            let y_used = grid.get(0).unwrap().len();
-            for vec in grid.iter() {
+        if let Some(first) = grid.first() {
+            let y_used = first.len();
+            for (row, vec) in grid.iter().enumerate() {
                 if vec.len() != y_used {
-                    panic!("All vectors in VectorGrid must be the same length");
+                    return Err(EngineError::RaggedGrid {
+                        row,
+                        expected: y_used,
+                        found: vec.len(),
+                    });
                 }
                 if vec.capacity() != y_size {
-                    panic!("All vectors in VectorGrid must have the same capacity");
+                    return Err(EngineError::UnevenCapacity {
+                        row,
+                        expected: y_size,
+                        found: vec.capacity(),
+                    });
                 }
             }
         }
 
-        FixedVectorLifeBoard {
+        //Every cell the board claims to have has to really be there
+        for column in grid.iter_mut() {
+            column.resize(y_size, false);
+        }
+        grid.resize(x_size, vec![false; y_size]);
+
+        Ok(FixedVectorLifeBoard {
             grid,
             x_size,
             y_size,
+        })
+    }
+}
+
+/// Create a new board in the same way as `FixedVectorLifeBoard::try_from_grid`, panicking if the vectors aren't all the same size
+impl From<Vec<Vec<bool>>> for FixedVectorLifeBoard {
+    fn from(grid: Vec<Vec<bool>>) -> Self {
+        match FixedVectorLifeBoard::try_from_grid(grid) {
+            Ok(board) => board,
+            Err(e) => panic!("{}", e),
         }
     }
 }
@@ -161,48 +211,15 @@ impl From<Vec<Vec<bool>>> for FixedVectorLifeBoard {
 mod test {
     use super::*;
 
-    #[test]
-    pub fn step_one_works_empty() {
-        let mut board = FixedVectorLifeBoard::empty();
-        board.step_one();
-        assert_eq!(0, board.get_live_count());
-    }
-
-    #[test]
-    pub fn step_one_works_blinker() {
-        let mut board = FixedVectorLifeBoard::empty();
-        board.set_live(2, 2);
-        board.set_live(2, 3);
-        board.set_live(2, 4);
-        board.set_live(5, 5);
-        board.step_one();
-        assert_eq!(3, board.get_live_count());
-        assert_eq!(true, board.is_live(1, 3));
-        assert_eq!(true, board.is_live(2, 3));
-        assert_eq!(true, board.is_live(3, 3));
-        assert_eq!(false, board.is_live(2, 2));
-        assert_eq!(false, board.is_live(2, 4));
-        assert_eq!(false, board.is_live(5, 5));
-
-        board.step_one();
-        assert_eq!(3, board.get_live_count());
-        assert_eq!(true, board.is_live(2, 2));
-        assert_eq!(true, board.is_live(2, 3));
-        assert_eq!(true, board.is_live(2, 4));
-        assert_eq!(false, board.is_live(1, 3));
-        assert_eq!(false, board.is_live(3, 3));
-        assert_eq!(false, board.is_live(5, 5));
-    }
-
     #[test]
     pub fn can_create_empty_board() {
         let board = FixedVectorLifeBoard::from(vec![]);
         assert_eq!(0, board.get_live_count());
         assert_eq!(0, board.x_size);
         assert_eq!(0, board.y_size);
-        assert_eq!(false, board.is_live(0, 0));
-        assert_eq!(false, board.is_live(0, 1));
-        assert_eq!(false, board.is_live(1, 1));
+        assert!(!board.is_live(0, 0));
+        assert!(!board.is_live(0, 1));
+        assert!(!board.is_live(1, 1));
     }
 
     #[test]
@@ -224,7 +241,7 @@ mod test {
         assert_eq!(vec1.len(), vec2.len());
         assert_ne!(vec1.capacity(), vec2.capacity());
 
-        FixedVectorLifeBoard::from(vec![vec1, vec2]);
+        let _ = FixedVectorLifeBoard::from(vec![vec1, vec2]);
     }
 
     #[test]
@@ -234,36 +251,97 @@ mod test {
         assert_eq!(2, board.x_size);
         assert_eq!(3, board.y_size);
         assert_eq!(3, board.get_live_count());
-        assert_eq!(false, board.is_live(0, 0));
-        assert_eq!(true, board.is_live(0, 1));
-        assert_eq!(false, board.is_live(0, 2));
-        assert_eq!(true, board.is_live(1, 0));
-        assert_eq!(false, board.is_live(1, 1));
-        assert_eq!(true, board.is_live(1, 2));
+        assert!(!board.is_live(0, 0));
+        assert!(board.is_live(0, 1));
+        assert!(!board.is_live(0, 2));
+        assert!(board.is_live(1, 0));
+        assert!(!board.is_live(1, 1));
+        assert!(board.is_live(1, 2));
     }
 
     #[test]
-    pub fn count_live_neighbors_works_at_borders() {
+    pub fn set_liveness_off_the_board_is_an_error() {
         let mut board = FixedVectorLifeBoard::empty();
-        board.set_live(0, 0);
-        board.set_live(0, 1);
+        assert_eq!(
+            Err(EngineError::OutOfBounds {
+                x: 256,
+                y: 3,
+                width: 256,
+                height: 256
+            }),
+            board.try_set_liveness(256, 3, true)
+        );
+        assert!(board.try_set_liveness(-1, 0, true).is_err());
+        assert_eq!(Ok(()), board.try_set_liveness(255, 255, true));
+        assert!(board.is_live(255, 255));
+    }
 
-        let neighbors = board.count_live_neighbors(0, 0);
-        assert_eq!(neighbors, 1);
+    #[test]
+    #[should_panic(expected = "outside the 256x256 board")]
+    pub fn set_liveness_off_the_board_panics() {
+        FixedVectorLifeBoard::empty().set_live(0, -1);
     }
 
     #[test]
-    pub fn count_live_neighbors_doesnt_count_self() {
-        let mut board = FixedVectorLifeBoard::empty();
-        for xi in 0..3 {
-            for yi in 0..3 {
-                board.set_live(xi, yi);
-            }
-        }
+    pub fn ragged_grid_is_an_error() {
+        let grid = vec![vec![false; 3], vec![true; 2]];
+        assert!(matches!(
+            FixedVectorLifeBoard::try_from_grid(grid),
+            Err(EngineError::RaggedGrid {
+                row: 1,
+                expected: 3,
+                found: 2
+            })
+        ));
+
+        let board = FixedVectorLifeBoard::try_from_grid(vec![vec![false; 3]; 3]).unwrap();
+        assert!(board.get_live_cells().is_empty());
+    }
 
-        assert_eq!(9, board.get_live_count());
+    #[test]
+    pub fn spare_capacity_is_dead_cells() {
+        let mut grid = Vec::with_capacity(10);
+        grid.push(vec![true]);
+        let mut board = FixedVectorLifeBoard::try_from_grid(grid).unwrap();
+        assert_eq!(Ok(()), board.try_set_liveness(5, 0, true));
+        assert!(board.is_live(0, 0));
+        assert!(board.is_live(5, 0));
+        assert!(board.try_set_liveness(10, 0, true).is_err());
+        board.step_one();
+        assert!(board.get_live_cells().is_empty());
+
+        let mut column = Vec::with_capacity(4);
+        column.push(false);
+        assert!(matches!(
+            FixedVectorLifeBoard::try_from_grid(vec![vec![false], column]),
+            Err(EngineError::UnevenCapacity {
+                row: 1,
+                expected: 1,
+                ..
+            })
+        ));
+    }
 
-        let neighbors = board.count_live_neighbors(1, 1);
-        assert_eq!(neighbors, 8);
+    #[test]
+    pub fn any_size_works() {
+        let mut board = FixedVectorLifeBoard::with_size(4, 1000);
+        assert!(board.try_set_liveness(3, 999, true).is_ok());
+        assert!(board.try_set_liveness(4, 0, true).is_err());
+        assert!(board.try_set_liveness(0, 1000, true).is_err());
+
+        //A blinker across the narrow board is cut down to a single row of two cells
+        board.set_live(3, 998);
+        board.set_live(3, 997);
+        board.step_one();
+        assert_eq!(2, board.get_live_count());
+        assert!(board.is_live(2, 998) && board.is_live(3, 998));
+    }
+
+    #[test]
+    pub fn zero_size_board_is_always_dead() {
+        let mut board = FixedVectorLifeBoard::with_size(0, 0);
+        board.step_one();
+        assert!(board.try_set_liveness(0, 0, true).is_err());
+        assert!(board.get_live_cells().is_empty());
     }
 }
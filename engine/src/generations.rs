@@ -0,0 +1,230 @@
+//! Iterators over the successive generations of a board, so analysis code can be written with `take`, `take_while`
+//! and friends instead of a hand-written stepping loop.
+//!
+//! Every iterator here starts with the board as it is now, as generation 0, and steps the board once before each
+//! item after that. So `take(n)` sees `n` generations and steps `n - 1` times, and the iterators never end on their own.
+
+use crate::life_board::LifeBoard;
+use crate::life_point::LifePoint;
+use crate::recorder::{generation_record, GenerationRecord};
+use crate::snapshot::BoardSnapshot;
+
+/// The cells of one generation of a board
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Generation {
+    pub generation: u64,
+    pub snapshot: BoardSnapshot,
+}
+
+/// The cells that changed going into one generation.
+/// Generation 0 has nothing before it, so every live cell counts as a birth.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GenerationDelta {
+    pub generation: u64,
+    /// Cells that are live in this generation but weren't in the previous one, in sorted order
+    pub births: Vec<LifePoint>,
+    /// Cells that were live in the previous generation but aren't in this one, in sorted order
+    pub deaths: Vec<LifePoint>,
+}
+
+/// Yields the number of each generation, stepping the board on the way. Nothing is copied out of the board,
+/// so this is the cheapest way to run it: `steps(board).nth(n)` leaves the board `n` generations on.
+pub struct Steps<'a> {
+    board: &'a mut dyn LifeBoard,
+    generation: Option<u64>,
+}
+
+impl Iterator for Steps<'_> {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        let generation = match self.generation {
+            Some(previous) => {
+                self.board.step_one();
+                previous + 1
+            }
+            None => 0,
+        };
+        self.generation = Some(generation);
+        Some(generation)
+    }
+}
+
+/// Yields a snapshot of every generation
+pub struct Snapshots<'a> {
+    steps: Steps<'a>,
+}
+
+impl Iterator for Snapshots<'_> {
+    type Item = Generation;
+
+    fn next(&mut self) -> Option<Generation> {
+        let generation = self.steps.next()?;
+        Some(Generation {
+            generation,
+            snapshot: BoardSnapshot::of(self.steps.board),
+        })
+    }
+}
+
+/// Yields the births and deaths going into every generation
+pub struct Deltas<'a> {
+    snapshots: Snapshots<'a>,
+    previous: Option<BoardSnapshot>,
+}
+
+impl Iterator for Deltas<'_> {
+    type Item = GenerationDelta;
+
+    fn next(&mut self) -> Option<GenerationDelta> {
+        let current = self.snapshots.next()?;
+        let empty = BoardSnapshot::of_cells(vec![]);
+        let (births, deaths) = current
+            .snapshot
+            .changes_since(self.previous.as_ref().unwrap_or(&empty));
+        let delta = GenerationDelta {
+            generation: current.generation,
+            births,
+            deaths,
+        };
+        self.previous = Some(current.snapshot);
+        Some(delta)
+    }
+}
+
+/// Yields the same statistics a `Recorder` would take of every generation, without keeping them all
+pub struct Stats<'a> {
+    snapshots: Snapshots<'a>,
+    previous: Option<BoardSnapshot>,
+}
+
+impl Iterator for Stats<'_> {
+    type Item = GenerationRecord;
+
+    fn next(&mut self) -> Option<GenerationRecord> {
+        let current = self.snapshots.next()?;
+        let record = generation_record(
+            current.generation,
+            self.previous.as_ref(),
+            &current.snapshot,
+        );
+        self.previous = Some(current.snapshot);
+        Some(record)
+    }
+}
+
+pub fn steps(board: &mut dyn LifeBoard) -> Steps<'_> {
+    Steps {
+        board,
+        generation: None,
+    }
+}
+
+pub fn snapshots(board: &mut dyn LifeBoard) -> Snapshots<'_> {
+    Snapshots {
+        steps: steps(board),
+    }
+}
+
+pub fn deltas(board: &mut dyn LifeBoard) -> Deltas<'_> {
+    Deltas {
+        snapshots: snapshots(board),
+        previous: None,
+    }
+}
+
+pub fn stats(board: &mut dyn LifeBoard) -> Stats<'_> {
+    Stats {
+        snapshots: snapshots(board),
+        previous: None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::pattern::Pattern;
+    use crate::recorder::Recorder;
+    use crate::DynamicVectorLifeBoard;
+
+    fn board_with(pattern: &Pattern) -> DynamicVectorLifeBoard {
+        let mut board = DynamicVectorLifeBoard::empty();
+        board.draw_pattern(pattern, &LifePoint::ORIGIN);
+        board
+    }
+
+    #[test]
+    pub fn steps_are_counted_from_zero() {
+        let mut board = board_with(&Pattern::GLIDER_SOUTHEAST());
+        let start = BoardSnapshot::of(&board);
+        assert_eq!(Some(0), steps(&mut board).next());
+        assert_eq!(start, BoardSnapshot::of(&board));
+
+        assert_eq!(Some(4), steps(&mut board).nth(4));
+        assert_eq!(start.translated(1, 1), BoardSnapshot::of(&board));
+    }
+
+    #[test]
+    pub fn take_steps_one_less_than_it_yields() {
+        let mut board = board_with(&Pattern::GLIDER_SOUTHEAST());
+        let start = BoardSnapshot::of(&board);
+        let generations: Vec<Generation> = snapshots(&mut board).take(5).collect();
+        assert_eq!(
+            vec![0, 1, 2, 3, 4],
+            generations
+                .iter()
+                .map(|g| g.generation)
+                .collect::<Vec<u64>>()
+        );
+        assert_eq!(start, generations[0].snapshot);
+        assert_eq!(start.translated(1, 1), generations[4].snapshot);
+        assert_eq!(generations[4].snapshot, BoardSnapshot::of(&board));
+    }
+
+    #[test]
+    pub fn deltas_of_a_blinker() {
+        let mut board = board_with(&Pattern::BLINKER());
+        let deltas: Vec<GenerationDelta> = deltas(&mut board).take(3).collect();
+
+        assert_eq!(3, deltas[0].births.len());
+        assert!(deltas[0].deaths.is_empty());
+        for delta in &deltas[1..] {
+            assert_eq!(2, delta.births.len());
+            assert_eq!(2, delta.deaths.len());
+        }
+        assert_eq!(deltas[1].births, deltas[2].deaths);
+        assert_eq!(deltas[1].deaths, deltas[2].births);
+    }
+
+    #[test]
+    pub fn stats_match_the_recorder() {
+        let mut board = board_with(&Pattern::ACORN());
+        let mut recorder = Recorder::new();
+        recorder.record(&board);
+        for _ in 0..50 {
+            board.step_one();
+            recorder.record(&board);
+        }
+        let recorded = recorder.into_series();
+        let streamed: Vec<GenerationRecord> =
+            stats(&mut board_with(&Pattern::ACORN())).take(51).collect();
+        assert_eq!(*recorded.get_records(), streamed);
+    }
+
+    #[test]
+    pub fn run_until_population_stabilizes() {
+        //A pre-block settles into a block after two generations
+        let mut board = board_with(&Pattern::from_tuples(vec![(0, 0), (1, 0), (0, 1)]));
+        let settled = stats(&mut board)
+            .take(100)
+            .find(|r| r.generation > 0 && r.births == 0 && r.deaths == 0)
+            .map(|r| r.generation);
+        assert_eq!(Some(2), settled);
+
+        let populations: Vec<u64> = stats(&mut board_with(&Pattern::BLOCK()))
+            .take_while(|r| r.generation == 0 || r.births + r.deaths > 0)
+            .map(|r| r.population)
+            .collect();
+        assert_eq!(vec![4], populations);
+    }
+}
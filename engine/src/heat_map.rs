@@ -0,0 +1,209 @@
+//! Per-cell activity over a run, for seeing where on the board things happened.
+
+use crate::error::{check_image_size, EngineError};
+use crate::life_board::LifeBoard;
+use crate::life_point::LifePoint;
+use crate::snapshot::BoardSnapshot;
+use std::collections::HashMap;
+
+/// What a heat map counts for each cell
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HeatMode {
+    /// The number of generations the cell was live
+    Alive,
+    /// The number of times the cell was born or died
+    Changes,
+}
+
+/// The board position of a heat map's top-left corner, and its counts as rows, top to bottom
+pub type HeatMatrix = (LifePoint, Vec<Vec<u64>>);
+
+/// Accumulates a count per cell by being shown the board once per generation.
+/// In `HeatMode::Changes` the first board recorded is the starting point, so it doesn't count as any changes.
+pub struct HeatMap {
+    mode: HeatMode,
+    counts: HashMap<LifePoint, u64>,
+    previous: Option<BoardSnapshot>,
+}
+
+impl HeatMap {
+    pub fn new(mode: HeatMode) -> HeatMap {
+        HeatMap {
+            mode,
+            counts: HashMap::new(),
+            previous: None,
+        }
+    }
+
+    pub fn record(&mut self, board: &dyn LifeBoard) {
+        let current = BoardSnapshot::of(board);
+        match self.mode {
+            HeatMode::Alive => {
+                for cell in current.get_cells() {
+                    *self.counts.entry(*cell).or_insert(0) += 1;
+                }
+            }
+            HeatMode::Changes => {
+                if let Some(previous) = &self.previous {
+                    let (births, deaths) = current.changes_since(previous);
+                    for cell in births.into_iter().chain(deaths) {
+                        *self.counts.entry(cell).or_insert(0) += 1;
+                    }
+                }
+                self.previous = Some(current);
+            }
+        }
+    }
+
+    pub fn get_mode(&self) -> HeatMode {
+        self.mode
+    }
+
+    pub fn get(&self, point: &LifePoint) -> u64 {
+        self.counts.get(point).copied().unwrap_or(0)
+    }
+
+    /// The highest count of any cell, or 0 if nothing has been counted
+    pub fn max_count(&self) -> u64 {
+        self.counts.values().copied().max().unwrap_or(0)
+    }
+
+    /// Get the smallest and largest corners of the box containing every counted cell, or `None` if there aren't any
+    pub fn get_bounds(&self) -> Option<(LifePoint, LifePoint)> {
+        BoardSnapshot::of_cells(self.counts.keys().copied().collect()).get_bounds()
+    }
+
+    /// Get the counts as rows of the bounding box, top to bottom, along with the board position of the top-left corner.
+    /// Returns `None` if nothing has been counted, and fails if the box has more than `MAX_IMAGE_PIXELS` cells,
+    /// as it can when gliders escape.
+    pub fn to_matrix(&self) -> Result<Option<HeatMatrix>, EngineError> {
+        let (min, max) = match self.get_bounds() {
+            Some(bounds) => bounds,
+            None => return Ok(None),
+        };
+        let side = |first: i64, last: i64| last as i128 - first as i128 + 1;
+        check_image_size(
+            side(min.get_x(), max.get_x()),
+            side(min.get_y(), max.get_y()),
+        )?;
+        let rows = (min.get_y()..=max.get_y())
+            .map(|y| {
+                (min.get_x()..=max.get_x())
+                    .map(|x| self.get(&LifePoint::new(x, y)))
+                    .collect()
+            })
+            .collect();
+        Ok(Some((min, rows)))
+    }
+
+    /// Export the bounding box as a binary (P5) PGM image, with the busiest cell white and untouched cells black.
+    /// Returns `None` or fails in the same cases as `to_matrix`.
+    pub fn to_pgm(&self) -> Result<Option<Vec<u8>>, EngineError> {
+        let (_, rows) = match self.to_matrix()? {
+            Some(matrix) => matrix,
+            None => return Ok(None),
+        };
+        //Scaled in u128 so that counts near u64::MAX don't overflow
+        let max = self.max_count() as u128;
+        let mut pgm = format!("P5\n{} {}\n255\n", rows[0].len(), rows.len()).into_bytes();
+        for row in rows {
+            pgm.extend(row.iter().map(|count| (*count as u128 * 255 / max) as u8));
+        }
+        Ok(Some(pgm))
+    }
+}
+
+/// Record the board as it is now, then after each of the next `generations` steps
+pub fn record_heat_map(board: &mut dyn LifeBoard, generations: u64, mode: HeatMode) -> HeatMap {
+    let mut heat_map = HeatMap::new(mode);
+    heat_map.record(board);
+    for _ in 0..generations {
+        board.step_one();
+        heat_map.record(board);
+    }
+    heat_map
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::pattern::Pattern;
+    use crate::DynamicArray2dLifeBoard;
+
+    fn blinker_heat_map(mode: HeatMode) -> HeatMap {
+        let mut board = DynamicArray2dLifeBoard::empty();
+        board.draw_pattern(&Pattern::BLINKER(), &LifePoint::new(0, 0));
+        record_heat_map(&mut board, 4, mode)
+    }
+
+    #[test]
+    pub fn alive_counts_generations() {
+        let heat_map = blinker_heat_map(HeatMode::Alive);
+        //The center is always live, and each arm is live every other generation
+        assert_eq!(5, heat_map.get(&LifePoint::new(0, 0)));
+        assert_eq!(3, heat_map.get(&LifePoint::new(-1, 0)));
+        assert_eq!(2, heat_map.get(&LifePoint::new(0, -1)));
+        assert_eq!(0, heat_map.get(&LifePoint::new(5, 5)));
+        assert_eq!(5, heat_map.max_count());
+    }
+
+    #[test]
+    pub fn changes_counts_births_and_deaths() {
+        let heat_map = blinker_heat_map(HeatMode::Changes);
+        assert_eq!(0, heat_map.get(&LifePoint::new(0, 0)));
+        assert_eq!(4, heat_map.get(&LifePoint::new(-1, 0)));
+        assert_eq!(4, heat_map.get(&LifePoint::new(0, 1)));
+    }
+
+    #[test]
+    pub fn matrix_covers_bounding_box() {
+        let (origin, rows) = blinker_heat_map(HeatMode::Alive)
+            .to_matrix()
+            .unwrap()
+            .unwrap();
+        assert_eq!(LifePoint::new(-1, -1), origin);
+        assert_eq!(vec![vec![0, 2, 0], vec![3, 5, 3], vec![0, 2, 0]], rows);
+    }
+
+    #[test]
+    pub fn pgm_export_works() {
+        let pgm = blinker_heat_map(HeatMode::Alive).to_pgm().unwrap().unwrap();
+        let header = b"P5\n3 3\n255\n";
+        assert_eq!(header, &pgm[..header.len()]);
+        assert_eq!(
+            vec![0, 102, 0, 153, 255, 153, 0, 102, 0],
+            pgm[header.len()..].to_vec()
+        );
+    }
+
+    #[test]
+    pub fn empty_heat_map_has_no_image() {
+        let heat_map = HeatMap::new(HeatMode::Alive);
+        assert_eq!(Ok(None), heat_map.to_matrix());
+        assert_eq!(Ok(None), heat_map.to_pgm());
+    }
+
+    #[test]
+    pub fn huge_heat_maps_are_rejected() {
+        //Two cells far apart, like a soup whose gliders have escaped
+        let mut heat_map = HeatMap::new(HeatMode::Alive);
+        heat_map.counts.insert(LifePoint::new(0, 0), 1);
+        heat_map
+            .counts
+            .insert(LifePoint::new(1_000_000, 1_000_000), 1);
+        assert!(matches!(
+            heat_map.to_matrix(),
+            Err(EngineError::InvalidImageSize { .. })
+        ));
+        assert!(heat_map.to_pgm().is_err());
+    }
+
+    #[test]
+    pub fn pgm_scales_huge_counts() {
+        let mut heat_map = HeatMap::new(HeatMode::Alive);
+        heat_map.counts.insert(LifePoint::new(0, 0), u64::MAX);
+        heat_map.counts.insert(LifePoint::new(1, 0), u64::MAX / 2);
+        let pgm = heat_map.to_pgm().unwrap().unwrap();
+        assert_eq!(vec![255, 127], pgm[pgm.len() - 2..].to_vec());
+    }
+}
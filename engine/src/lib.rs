@@ -1,17 +1,41 @@
+pub mod analysis;
+pub mod animation;
+pub mod apgcode;
 mod array_grid;
+pub mod census;
+#[cfg(test)]
+mod conformance;
 mod dynamic_array2d_board;
 mod dynamic_vector_board;
+pub mod error;
 mod fixed_bitfield_board;
 mod fixed_vector_board;
+pub mod generations;
+pub mod heat_map;
 pub mod life_board;
 mod life_cell;
+pub mod life_point;
+pub mod observer;
 pub mod pattern;
+#[cfg(test)]
+mod properties;
+pub mod recorder;
+pub mod rectangle;
+pub mod registry;
+pub mod render;
+pub mod snapshot;
+pub mod soup;
+pub mod svg;
 
 pub use dynamic_array2d_board::DynamicArray2dLifeBoard;
 pub use dynamic_vector_board::DynamicVectorLifeBoard;
+pub use error::EngineError;
 pub use fixed_bitfield_board::FixedBitfieldLifeBoard;
 pub use fixed_vector_board::FixedVectorLifeBoard;
 pub use life_board::LifeBoard;
+pub use life_point::LifePoint;
+pub use rectangle::Rectangle;
+pub use snapshot::BoardSnapshot;
 
 pub fn new_fixed_vector_board() -> FixedVectorLifeBoard {
     FixedVectorLifeBoard::empty()
@@ -1,8 +1,13 @@
+use crate::error::EngineError;
+use crate::life_point::LifePoint;
 use crate::pattern::Pattern;
-use std::convert::From;
+use crate::rectangle::Rectangle;
+use crate::snapshot::BoardSnapshot;
+use std::collections::HashSet;
 
 pub trait LifeBoard {
     fn is_live(&self, x: i64, y: i64) -> bool;
+    /// Set a cell live or dead. Boards with a fixed size panic if the cell is off the board; use `try_set_liveness` to avoid that.
     fn set_liveness(&mut self, x: i64, y: i64, is_live: bool);
     fn count_live_neighbors(&self, x: i64, y: i64) -> u8;
     fn step_one(&mut self);
@@ -11,50 +16,366 @@ pub trait LifeBoard {
     /// This isn't really useful in an API/game sense, but can be useful for seeing that the board implementation is working as expected.
     fn get_stats(&self) -> Vec<(&str, String)>;
 
-    fn draw_pattern(&mut self, pattern: &Pattern, center: &BoardPoint) {
+    /// Get every live cell on the board, in no particular order
+    fn get_live_cells(&self) -> Vec<LifePoint>;
+
+    /// The cells the board can hold, or `None` if it grows to fit any cell.
+    /// Region edits are cut off at these bounds rather than writing cells the board doesn't have.
+    fn get_bounds(&self) -> Option<Rectangle>;
+
+    /// Get every live cell inside the region, in no particular order.
+    /// This is one call for a whole region, so it's much cheaper than calling `is_live` for every cell in it.
+    fn get_live_cells_in(&self, region: &Rectangle) -> Vec<LifePoint> {
+        self.get_live_cells()
+            .into_iter()
+            .filter(|cell| region.contains(cell))
+            .collect()
+    }
+
+    /// Count the live cells inside the region
+    fn count_live_cells_in(&self, region: &Rectangle) -> u64 {
+        self.get_live_cells_in(region).len() as u64
+    }
+
+    /// Check if this board has exactly the same live cells as another board, regardless of how either board is implemented
+    fn has_same_cells(&self, other: &dyn LifeBoard) -> bool {
+        BoardSnapshot::of_cells(self.get_live_cells()) == BoardSnapshot::of(other)
+    }
+
+    /// Make the pattern's cells live, with the pattern's origin at `center`.
+    /// Boards with a fixed size panic if any of the pattern is off the board; use `try_draw_pattern` to avoid that.
+    fn draw_pattern(&mut self, pattern: &Pattern, center: &LifePoint) {
         for pattern_point in pattern.get_points() {
-            let board_point = pattern_point.offset(center.x, center.y);
+            let board_point = pattern_point.offset(center.get_x(), center.get_y());
             self.set_live_point(&board_point);
         }
     }
 
+    /// Draw the pattern, or return an error without drawing any of it if it doesn't all fit on the board
+    fn try_draw_pattern(
+        &mut self,
+        pattern: &Pattern,
+        center: &LifePoint,
+    ) -> Result<(), EngineError> {
+        if let Some(bounds) = self.get_bounds() {
+            let off_board = pattern
+                .get_points()
+                .iter()
+                .map(|point| point.offset(center.get_x(), center.get_y()))
+                .find(|cell| !bounds.contains(cell));
+            if let Some(cell) = off_board {
+                return Err(EngineError::OutOfBounds {
+                    x: cell.get_x(),
+                    y: cell.get_y(),
+                    width: bounds.get_width(),
+                    height: bounds.get_height(),
+                });
+            }
+        }
+        self.draw_pattern(pattern, center);
+        Ok(())
+    }
+
+    /// Kill every cell inside the region
+    fn clear_region(&mut self, region: &Rectangle) {
+        set_region(self, region, &HashSet::new());
+    }
+
+    /// Replace the region with copies of the pattern, laid side by side from the region's top-left corner.
+    /// Copies that don't fit are cut off at the edge of the region.
+    fn fill_region(&mut self, region: &Rectangle, pattern: &Pattern) {
+        let tile = Rectangle::from_points(pattern.get_points());
+        let mut cells = HashSet::new();
+        if !tile.is_empty() {
+            for tile_y in
+                (region.get_y_min()..=region.get_y_max()).step_by(tile.get_height() as usize)
+            {
+                for tile_x in
+                    (region.get_x_min()..=region.get_x_max()).step_by(tile.get_width() as usize)
+                {
+                    let shift =
+                        LifePoint::new(tile_x - tile.get_x_min(), tile_y - tile.get_y_min());
+                    cells.extend(
+                        pattern
+                            .get_points()
+                            .iter()
+                            .map(|point| *point + shift)
+                            .filter(|cell| region.contains(cell)),
+                    );
+                }
+            }
+        }
+        set_region(self, region, &cells);
+    }
+
+    /// Replace the region with random cells, each live with probability `density`.
+    /// `random` gives numbers in the range `[0, 1)`, one per cell, like `SoupRng::next_f64`.
+    fn randomize_region(
+        &mut self,
+        region: &Rectangle,
+        density: f64,
+        random: &mut dyn FnMut() -> f64,
+    ) {
+        let cells = on_board(self, region)
+            .points()
+            .filter(|_| random() < density)
+            .collect();
+        set_region(self, region, &cells);
+    }
+
+    /// Make every live cell in the region dead and every dead cell live
+    fn invert_region(&mut self, region: &Rectangle) {
+        let region = on_board(self, region);
+        let live: HashSet<LifePoint> = self.get_live_cells_in(&region).into_iter().collect();
+        let cells = region
+            .points()
+            .filter(|cell| !live.contains(cell))
+            .collect();
+        set_region(self, &region, &cells);
+    }
+
+    /// Copy the region so its top-left corner is at `destination`, replacing whatever was there.
+    /// The source and destination may overlap.
+    fn copy_region(&mut self, source: &Rectangle, destination: &LifePoint) {
+        let (target, cells) = shift_region(self, source, destination);
+        set_region(self, &target, &cells);
+    }
+
+    /// Move the region so its top-left corner is at `destination`, replacing whatever was there
+    /// and leaving the uncovered part of the source empty. The source and destination may overlap.
+    fn move_region(&mut self, source: &Rectangle, destination: &LifePoint) {
+        let (target, cells) = shift_region(self, source, destination);
+        //Only the part of the source that the target doesn't cover needs clearing, so no cell is written twice
+        for cell in self.get_live_cells_in(source) {
+            if !target.contains(&cell) {
+                self.set_liveness_point(&cell, false);
+            }
+        }
+        set_region(self, &target, &cells);
+    }
+
+    /// Set a cell live or dead, or return an error instead of panicking if the board can't hold it
+    fn try_set_liveness(&mut self, x: i64, y: i64, is_live: bool) -> Result<(), EngineError>;
+
     fn set_live(&mut self, x: i64, y: i64) {
         self.set_liveness(x, y, true);
     }
 
-    fn is_live_point(&self, point: &BoardPoint) -> bool {
-        self.is_live(point.x, point.y)
+    fn is_live_point(&self, point: &LifePoint) -> bool {
+        self.is_live(point.get_x(), point.get_y())
     }
 
-    fn set_live_point(&mut self, point: &BoardPoint) {
+    fn set_live_point(&mut self, point: &LifePoint) {
         self.set_liveness_point(point, true);
     }
 
-    fn set_liveness_point(&mut self, point: &BoardPoint, liveness: bool) {
-        self.set_liveness(point.x, point.y, liveness);
+    fn set_liveness_point(&mut self, point: &LifePoint, liveness: bool) {
+        self.set_liveness(point.get_x(), point.get_y(), liveness);
     }
 }
 
-pub struct BoardPoint {
-    x: i64,
-    y: i64,
+/// The part of the region that's on the board
+fn on_board<B: LifeBoard + ?Sized>(board: &B, region: &Rectangle) -> Rectangle {
+    board
+        .get_bounds()
+        .map_or(*region, |bounds| bounds.intersection(region))
 }
 
-impl BoardPoint {
-    pub fn new(x: i64, y: i64) -> BoardPoint {
-        BoardPoint { x, y }
+/// Make the live cells in the region exactly `cells`, ignoring any part of the region that's off the board.
+/// Only cells that change are written, so boards never see a cell set to the state it already has.
+fn set_region<B: LifeBoard + ?Sized>(
+    board: &mut B,
+    region: &Rectangle,
+    cells: &HashSet<LifePoint>,
+) {
+    let region = on_board(board, region);
+    let live: HashSet<LifePoint> = board.get_live_cells_in(&region).into_iter().collect();
+    for cell in live.difference(cells) {
+        board.set_liveness_point(cell, false);
+    }
+    for cell in cells.difference(&live).filter(|cell| region.contains(cell)) {
+        board.set_liveness_point(cell, true);
     }
+}
 
-    pub fn offset(&self, x: i64, y: i64) -> BoardPoint {
-        BoardPoint {
-            x: self.x + x,
-            y: self.y + y,
-        }
+/// The region with its top-left corner moved to `destination`, and the source's live cells moved with it
+fn shift_region<B: LifeBoard + ?Sized>(
+    board: &B,
+    source: &Rectangle,
+    destination: &LifePoint,
+) -> (Rectangle, HashSet<LifePoint>) {
+    if source.is_empty() {
+        return (Rectangle::empty(), HashSet::new());
     }
+    let shift = *destination - LifePoint::new(source.get_x_min(), source.get_y_min());
+    let target = Rectangle::new(
+        destination.get_x(),
+        destination.get_y(),
+        source.get_width(),
+        source.get_height(),
+    );
+    let cells = board
+        .get_live_cells_in(source)
+        .into_iter()
+        .map(|cell| cell + shift)
+        .collect();
+    (target, cells)
 }
 
-impl From<&(i64, i64)> for BoardPoint {
-    fn from((x, y): &(i64, i64)) -> Self {
-        BoardPoint::new(x.clone(), y.clone())
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::soup::SoupRng;
+    use crate::{DynamicVectorLifeBoard, FixedBitfieldLifeBoard, FixedVectorLifeBoard};
+
+    fn cells(board: &dyn LifeBoard) -> Vec<LifePoint> {
+        BoardSnapshot::of(board).get_cells().clone()
+    }
+
+    fn points(coords: Vec<(i64, i64)>) -> Vec<LifePoint> {
+        BoardSnapshot::of_pattern(&Pattern::from_tuples(coords))
+            .get_cells()
+            .clone()
+    }
+
+    #[test]
+    pub fn clear_region_only_clears_inside() {
+        let mut board = DynamicVectorLifeBoard::empty();
+        board.draw_pattern(&Pattern::BLOCK(), &LifePoint::new(0, 0));
+        board.set_live(5, 5);
+        board.clear_region(&Rectangle::new(0, 0, 2, 2));
+        assert_eq!(points(vec![(5, 5)]), cells(&board));
+    }
+
+    #[test]
+    pub fn fill_region_tiles_the_pattern() {
+        let mut board = DynamicVectorLifeBoard::empty();
+        board.set_live(1, 0);
+        //A single cell tiles to fill the whole region
+        board.fill_region(
+            &Rectangle::new(0, 0, 2, 2),
+            &Pattern::from_tuples(vec![(7, 7)]),
+        );
+        assert_eq!(4, board.get_live_cells().len());
+
+        //A vertical blinker is 1x3, so a 3x4 region holds one and a third rows of them
+        let mut board = DynamicVectorLifeBoard::empty();
+        let blinker = Pattern::from_tuples(vec![(0, -1), (0, 0), (0, 1)]);
+        board.fill_region(&Rectangle::new(10, 10, 3, 4), &blinker);
+        assert_eq!(12, board.get_live_cells().len());
+        board.fill_region(&Rectangle::new(10, 10, 3, 4), &Pattern::new(vec![]));
+        assert!(board.get_live_cells().is_empty());
+    }
+
+    #[test]
+    pub fn randomize_region_is_reproducible() {
+        let region = Rectangle::new(-5, -5, 20, 20);
+        let mut first = DynamicVectorLifeBoard::empty();
+        let mut rng = SoupRng::from_seed("seed");
+        first.randomize_region(&region, 0.5, &mut || rng.next_f64());
+        let mut second = DynamicVectorLifeBoard::empty();
+        let mut rng = SoupRng::from_seed("seed");
+        second.randomize_region(&region, 0.5, &mut || rng.next_f64());
+
+        assert!(first.has_same_cells(&second));
+        let live = first.get_live_cells().len();
+        assert!(live > 100 && live < 300);
+        assert!(first
+            .get_live_cells()
+            .iter()
+            .all(|cell| region.contains(cell)));
+    }
+
+    #[test]
+    pub fn invert_region_twice_is_unchanged() {
+        let mut board = FixedBitfieldLifeBoard::empty();
+        board.draw_pattern(&Pattern::GLIDER_SOUTHEAST(), &LifePoint::new(10, 10));
+        let before = cells(&board);
+        let region = Rectangle::new(9, 9, 4, 3);
+        let live_inside = board.count_live_cells_in(&region);
+
+        board.invert_region(&region);
+        assert_eq!(12 - live_inside, board.count_live_cells_in(&region));
+        board.invert_region(&region);
+        assert_eq!(before, cells(&board));
+    }
+
+    #[test]
+    pub fn copy_region_replaces_the_destination() {
+        let mut board = DynamicVectorLifeBoard::empty();
+        board.set_live(0, 0);
+        board.set_live(11, 11);
+        board.copy_region(&Rectangle::new(0, 0, 2, 2), &LifePoint::new(10, 10));
+        assert_eq!(points(vec![(0, 0), (10, 10)]), cells(&board));
+    }
+
+    #[test]
+    pub fn move_region_can_overlap() {
+        let mut board = DynamicVectorLifeBoard::empty();
+        board.draw_pattern(&Pattern::BLINKER(), &LifePoint::new(1, 0));
+        board.move_region(&Rectangle::new(0, 0, 3, 1), &LifePoint::new(1, 0));
+        assert_eq!(points(vec![(1, 0), (2, 0), (3, 0)]), cells(&board));
+    }
+
+    #[test]
+    pub fn region_edits_stop_at_fixed_board_edges() {
+        let boards: Vec<Box<dyn LifeBoard>> = vec![
+            Box::new(FixedVectorLifeBoard::with_size(8, 8)),
+            Box::new(FixedBitfieldLifeBoard::with_size(8, 8)),
+        ];
+        for mut board in boards {
+            let corner = Rectangle::new(-2, -2, 4, 4);
+            board.invert_region(&corner);
+            assert_eq!(
+                points(vec![(0, 0), (1, 0), (0, 1), (1, 1)]),
+                cells(board.as_ref())
+            );
+
+            board.copy_region(&Rectangle::new(0, 0, 4, 4), &LifePoint::new(6, 6));
+            assert_eq!(4, board.count_live_cells_in(&Rectangle::new(6, 6, 2, 2)));
+            board.move_region(&Rectangle::new(0, 0, 2, 2), &LifePoint::new(7, -1));
+            assert_eq!(
+                points(vec![(6, 6), (7, 6), (6, 7), (7, 7), (7, 0)]),
+                cells(board.as_ref())
+            );
+
+            board.fill_region(
+                &Rectangle::new(4, 4, 10, 10),
+                &Pattern::from_tuples(vec![(0, 0)]),
+            );
+            assert_eq!(16, board.count_live_cells_in(&Rectangle::new(4, 4, 4, 4)));
+            let mut rng = SoupRng::from_seed("edge");
+            board.randomize_region(&Rectangle::new(-100, -100, 300, 300), 1.0, &mut || {
+                rng.next_f64()
+            });
+            assert_eq!(64, board.get_live_cells().len());
+            board.clear_region(&Rectangle::new(-100, -100, 300, 300));
+            assert!(board.get_live_cells().is_empty());
+        }
+    }
+
+    #[test]
+    pub fn try_draw_pattern_draws_all_or_nothing() {
+        let mut board = FixedBitfieldLifeBoard::with_size(8, 8);
+        assert_eq!(
+            Err(EngineError::OutOfBounds {
+                x: 8,
+                y: 7,
+                width: 8,
+                height: 8
+            }),
+            board.try_draw_pattern(&Pattern::BLINKER(), &LifePoint::new(7, 7))
+        );
+        assert!(board.get_live_cells().is_empty());
+        assert_eq!(
+            Ok(()),
+            board.try_draw_pattern(&Pattern::BLINKER(), &LifePoint::new(6, 7))
+        );
+        assert_eq!(3, board.get_live_cells().len());
+
+        let mut board = DynamicVectorLifeBoard::empty();
+        let far = LifePoint::new(-1_000_000, 1_000_000);
+        assert_eq!(Ok(()), board.try_draw_pattern(&Pattern::ACORN(), &far));
+        assert_eq!(7, board.get_live_cells().len());
     }
 }
@@ -6,6 +6,8 @@ pub struct LifeCell {
 impl LifeCell {
     const STATE_MASK: u8 = 0b00010000;
     const COUNT_MASK: u8 = 0b00001111;
+    /// A cell can't have more live neighbors than this, which keeps the count well inside `COUNT_MASK`
+    const MAX_NEIGHBORS: u8 = 8;
 
     pub fn is_live(&self) -> bool {
         self.data & LifeCell::STATE_MASK != 0
@@ -15,6 +17,7 @@ impl LifeCell {
         self.data & LifeCell::COUNT_MASK
     }
 
+    #[allow(dead_code)]
     pub fn create_dead() -> LifeCell {
         LifeCell { data: 0 }
     }
@@ -25,19 +28,32 @@ impl LifeCell {
     }
 
     pub fn set_alive(&mut self) {
-        self.data = self.data | LifeCell::STATE_MASK;
+        self.data |= LifeCell::STATE_MASK;
     }
 
     pub fn set_dead(&mut self) {
-        self.data = self.data & !LifeCell::STATE_MASK;
+        self.data &= !LifeCell::STATE_MASK;
     }
 
+    /// Add one to the neighbor count. The count never goes above eight, so it can't carry into the state bit;
+    /// a board that tries to go further has a bug, which debug builds catch.
     pub fn increment_neighbors(&mut self) {
-        self.data = self.data + 1;
+        debug_assert!(
+            self.neighbors_count() < LifeCell::MAX_NEIGHBORS,
+            "LifeCell already has {} neighbors",
+            LifeCell::MAX_NEIGHBORS
+        );
+        if self.neighbors_count() < LifeCell::MAX_NEIGHBORS {
+            self.data += 1;
+        }
     }
 
+    /// Take one from the neighbor count, which never goes below zero
     pub fn decrement_neighbors(&mut self) {
-        self.data = self.data - 1;
+        debug_assert!(self.neighbors_count() > 0, "LifeCell has no neighbors");
+        if self.neighbors_count() > 0 {
+            self.data -= 1;
+        }
     }
 }
 
@@ -48,31 +64,62 @@ mod test {
     #[test]
     pub fn life_cell_works() {
         let mut lc = LifeCell::create_dead();
-        assert_eq!(false, lc.is_live());
+        assert!(!lc.is_live());
         assert_eq!(0, lc.neighbors_count());
 
         lc.set_alive();
-        assert_eq!(true, lc.is_live());
+        assert!(lc.is_live());
         assert_eq!(0, lc.neighbors_count());
 
         lc.increment_neighbors();
-        assert_eq!(true, lc.is_live());
+        assert!(lc.is_live());
         assert_eq!(1, lc.neighbors_count());
 
         lc.increment_neighbors();
-        assert_eq!(true, lc.is_live());
+        assert!(lc.is_live());
         assert_eq!(2, lc.neighbors_count());
 
         lc.set_dead();
-        assert_eq!(false, lc.is_live());
+        assert!(!lc.is_live());
         assert_eq!(2, lc.neighbors_count());
 
         lc.decrement_neighbors();
-        assert_eq!(false, lc.is_live());
+        assert!(!lc.is_live());
         assert_eq!(1, lc.neighbors_count());
 
         lc.decrement_neighbors();
-        assert_eq!(false, lc.is_live());
+        assert!(!lc.is_live());
         assert_eq!(0, lc.neighbors_count());
     }
+
+    #[test]
+    #[cfg(not(debug_assertions))]
+    pub fn counts_stay_in_range() {
+        let mut lc = LifeCell::create_dead();
+        lc.decrement_neighbors();
+        assert_eq!(0, lc.neighbors_count());
+
+        for _ in 0..20 {
+            lc.increment_neighbors();
+        }
+        assert_eq!(8, lc.neighbors_count());
+        assert!(!lc.is_live());
+    }
+
+    #[test]
+    #[should_panic(expected = "no neighbors")]
+    #[cfg(debug_assertions)]
+    pub fn decrementing_zero_is_caught() {
+        LifeCell::create_dead().decrement_neighbors();
+    }
+
+    #[test]
+    #[should_panic(expected = "already has 8 neighbors")]
+    #[cfg(debug_assertions)]
+    pub fn incrementing_past_eight_is_caught() {
+        let mut lc = LifeCell::create_dead();
+        for _ in 0..9 {
+            lc.increment_neighbors();
+        }
+    }
 }
@@ -1,16 +1,25 @@
 use std::convert::From;
 use std::fmt;
+use std::ops::{Add, AddAssign, Sub, SubAssign};
+
 /**
- * An immutable point on the Life board. In keeping with Eric Lippert's implementation, the Y-coordinate increases when going *up*, rather than the screen default of going *down*
- * ie, the origin is the "bottom left" rather than the "top left"
+ * An immutable point on the Life board. The Y-coordinate increases when going *down*, matching the screen,
+ * ie, the origin is the "top left" of a pattern rather than the "bottom left"
  */
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LifePoint {
     x: i64,
     y: i64,
 }
 
 impl LifePoint {
+    pub const ORIGIN: LifePoint = LifePoint::new(0, 0);
+
+    pub const fn new(x: i64, y: i64) -> LifePoint {
+        LifePoint { x, y }
+    }
+
     pub fn get_x(&self) -> i64 {
         self.x
     }
@@ -19,8 +28,35 @@ impl LifePoint {
         self.y
     }
 
-    pub fn new(x: i64, y: i64) {
-        LifePoint { x, y };
+    pub fn offset(&self, x: i64, y: i64) -> LifePoint {
+        LifePoint {
+            x: self.x + x,
+            y: self.y + y,
+        }
+    }
+
+    /// The eight points touching this one, including diagonally
+    pub fn neighbors(&self) -> impl Iterator<Item = LifePoint> {
+        let center = *self;
+        (-1..=1)
+            .flat_map(|dy| (-1..=1).map(move |dx| (dx, dy)))
+            .filter(|(dx, dy)| *dx != 0 || *dy != 0)
+            .map(move |(dx, dy)| center.offset(dx, dy))
+    }
+
+    /// The number of king moves between the points, which is how far a signal can travel at the speed of light
+    pub fn chebyshev_distance(&self, other: &LifePoint) -> u64 {
+        let (dx, dy) = self.abs_differences(other);
+        dx.max(dy)
+    }
+
+    pub fn manhattan_distance(&self, other: &LifePoint) -> u64 {
+        let (dx, dy) = self.abs_differences(other);
+        dx + dy
+    }
+
+    fn abs_differences(&self, other: &LifePoint) -> (u64, u64) {
+        (self.x.abs_diff(other.x), self.y.abs_diff(other.y))
     }
 }
 
@@ -30,6 +66,34 @@ impl fmt::Display for LifePoint {
     }
 }
 
+impl Add for LifePoint {
+    type Output = LifePoint;
+
+    fn add(self, other: LifePoint) -> LifePoint {
+        self.offset(other.x, other.y)
+    }
+}
+
+impl Sub for LifePoint {
+    type Output = LifePoint;
+
+    fn sub(self, other: LifePoint) -> LifePoint {
+        self.offset(-other.x, -other.y)
+    }
+}
+
+impl AddAssign for LifePoint {
+    fn add_assign(&mut self, other: LifePoint) {
+        *self = *self + other;
+    }
+}
+
+impl SubAssign for LifePoint {
+    fn sub_assign(&mut self, other: LifePoint) {
+        *self = *self - other;
+    }
+}
+
 impl From<(i32, i32)> for LifePoint {
     fn from(point_coords: (i32, i32)) -> LifePoint {
         LifePoint {
@@ -48,29 +112,73 @@ impl From<(i64, i64)> for LifePoint {
     }
 }
 
+impl From<&(i64, i64)> for LifePoint {
+    fn from((x, y): &(i64, i64)) -> Self {
+        LifePoint::new(*x, *y)
+    }
+}
+
 #[cfg(test)]
 #[allow(non_snake_case)]
 mod tests {
     use super::*;
+    use std::collections::HashSet;
 
     #[test]
     fn can_create_LifePoint() {
         let p = LifePoint { x: 1, y: 3 };
         assert_eq!(p.get_x(), 1);
         assert_eq!(p.get_y(), 3);
+        assert_eq!(p, LifePoint::new(1, 3));
     }
 
     #[test]
     fn can_create_LifePoint_from_i32_tuple() {
-        let p = LifePoint::from((1 as i32, 2 as i32));
+        let p = LifePoint::from((1_i32, 2_i32));
         assert_eq!(p.get_x(), 1);
         assert_eq!(p.get_y(), 2);
     }
 
     #[test]
     fn can_create_LifePoint_from_i64_tuple() {
-        let p = LifePoint::from((1 as i64, 2 as i64));
+        let p = LifePoint::from((1_i64, 2_i64));
         assert_eq!(p.get_x(), 1);
         assert_eq!(p.get_y(), 2);
     }
+
+    #[test]
+    fn arithmetic_works() {
+        let mut p = LifePoint::new(1, 2) + LifePoint::new(10, -5);
+        assert_eq!(LifePoint::new(11, -3), p);
+        assert_eq!(LifePoint::new(1, 2), p - LifePoint::new(10, -5));
+
+        p -= LifePoint::new(11, -3);
+        assert_eq!(LifePoint::ORIGIN, p);
+        p += LifePoint::new(4, 4);
+        assert_eq!(LifePoint::new(4, 4), p);
+    }
+
+    #[test]
+    fn neighbors_are_the_surrounding_eight() {
+        let center = LifePoint::new(-2, 7);
+        let neighbors: HashSet<LifePoint> = center.neighbors().collect();
+        assert_eq!(8, neighbors.len());
+        assert!(!neighbors.contains(&center));
+        assert!(neighbors.iter().all(|n| n.chebyshev_distance(&center) == 1));
+    }
+
+    #[test]
+    fn distances_work() {
+        let a = LifePoint::new(-1, 2);
+        let b = LifePoint::new(3, -5);
+        assert_eq!(7, a.chebyshev_distance(&b));
+        assert_eq!(11, a.manhattan_distance(&b));
+        assert_eq!(0, a.manhattan_distance(&a));
+    }
+
+    #[test]
+    fn points_are_ordered_by_x_then_y() {
+        assert!(LifePoint::new(0, 5) < LifePoint::new(1, 0));
+        assert!(LifePoint::new(1, 0) < LifePoint::new(1, 1));
+    }
 }
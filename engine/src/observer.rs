@@ -0,0 +1,333 @@
+//! Notifications of everything that happens to a board, so loggers, recorders and renderers can follow along
+//! without every `LifeBoard` implementation (or every main loop) having to know about them.
+
+use crate::error::EngineError;
+use crate::life_board::LifeBoard;
+use crate::life_point::LifePoint;
+use crate::rectangle::Rectangle;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Something that happened to an observed board. Observers are told after the board has changed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BoardEvent {
+    /// A single cell changed. Writes that leave a cell as it was aren't reported.
+    CellChanged { cell: LifePoint, is_live: bool },
+    /// The board moved on a generation, and is now at `generation`
+    Stepped { generation: u64 },
+    /// Every cell in the region was killed, as one edit rather than a `CellChanged` for each cell
+    Cleared { region: Rectangle },
+}
+
+pub trait BoardObserver {
+    fn on_event(&mut self, board: &dyn LifeBoard, event: &BoardEvent);
+}
+
+/// Any closure taking the board and the event can be an observer, which is handy for logging
+impl<F> BoardObserver for F
+where
+    F: FnMut(&dyn LifeBoard, &BoardEvent),
+{
+    fn on_event(&mut self, board: &dyn LifeBoard, event: &BoardEvent) {
+        self(board, event)
+    }
+}
+
+/// A shared observer, so the code that added it can still get at it, like a recorder whose series is shown elsewhere
+impl<O: BoardObserver> BoardObserver for Rc<RefCell<O>> {
+    fn on_event(&mut self, board: &dyn LifeBoard, event: &BoardEvent) {
+        self.borrow_mut().on_event(board, event)
+    }
+}
+
+/// Identifies an observer so it can be removed later
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ObserverId(u64);
+
+/// Wraps any board and tells its observers about every change made through it.
+/// Changes made to the inner board directly, bypassing the wrapper, aren't seen.
+///
+/// Single-cell edits, and the region edits `fill_region`, `randomize_region`, `invert_region`, `copy_region` and
+/// `move_region`, send a `CellChanged` for each cell that actually changes. `clear_region` sends one `Cleared`
+/// instead, for the part of the region that's on the board, and only if anything there was live.
+pub struct ObservedBoard {
+    board: Box<dyn LifeBoard>,
+    observers: Vec<(ObserverId, Box<dyn BoardObserver>)>,
+    next_id: u64,
+    generation: u64,
+}
+
+impl ObservedBoard {
+    pub fn new(board: Box<dyn LifeBoard>) -> ObservedBoard {
+        ObservedBoard {
+            board,
+            observers: vec![],
+            next_id: 0,
+            generation: 0,
+        }
+    }
+
+    pub fn add_observer(&mut self, observer: Box<dyn BoardObserver>) -> ObserverId {
+        let id = ObserverId(self.next_id);
+        self.next_id += 1;
+        self.observers.push((id, observer));
+        id
+    }
+
+    /// Stop notifying the observer, handing it back if it was there
+    pub fn remove_observer(&mut self, id: ObserverId) -> Option<Box<dyn BoardObserver>> {
+        let index = self.observers.iter().position(|(i, _)| *i == id)?;
+        Some(self.observers.remove(index).1)
+    }
+
+    /// Number of steps taken through this wrapper
+    pub fn get_generation(&self) -> u64 {
+        self.generation
+    }
+
+    pub fn get_board(&self) -> &dyn LifeBoard {
+        self.board.as_ref()
+    }
+
+    /// Unwrap the inner board, dropping the observers
+    pub fn into_board(self) -> Box<dyn LifeBoard> {
+        self.board
+    }
+
+    fn notify(&mut self, event: BoardEvent) {
+        for (_, observer) in self.observers.iter_mut() {
+            observer.on_event(self.board.as_ref(), &event);
+        }
+    }
+}
+
+impl LifeBoard for ObservedBoard {
+    fn is_live(&self, x: i64, y: i64) -> bool {
+        self.board.is_live(x, y)
+    }
+
+    fn set_liveness(&mut self, x: i64, y: i64, is_live: bool) {
+        let was_live = self.board.is_live(x, y);
+        self.board.set_liveness(x, y, is_live);
+        if was_live != is_live {
+            self.notify(BoardEvent::CellChanged {
+                cell: LifePoint::new(x, y),
+                is_live,
+            });
+        }
+    }
+
+    fn try_set_liveness(&mut self, x: i64, y: i64, is_live: bool) -> Result<(), EngineError> {
+        let was_live = self.board.is_live(x, y);
+        self.board.try_set_liveness(x, y, is_live)?;
+        if was_live != is_live {
+            self.notify(BoardEvent::CellChanged {
+                cell: LifePoint::new(x, y),
+                is_live,
+            });
+        }
+        Ok(())
+    }
+
+    fn count_live_neighbors(&self, x: i64, y: i64) -> u8 {
+        self.board.count_live_neighbors(x, y)
+    }
+
+    fn step_one(&mut self) {
+        self.board.step_one();
+        self.generation += 1;
+        self.notify(BoardEvent::Stepped {
+            generation: self.generation,
+        });
+    }
+
+    fn get_stats(&self) -> Vec<(&str, String)> {
+        let mut stats = self.board.get_stats();
+        stats.push(("observers", self.observers.len().to_string()));
+        stats
+    }
+
+    fn get_live_cells(&self) -> Vec<LifePoint> {
+        self.board.get_live_cells()
+    }
+
+    fn get_bounds(&self) -> Option<Rectangle> {
+        self.board.get_bounds()
+    }
+
+    fn get_live_cells_in(&self, region: &Rectangle) -> Vec<LifePoint> {
+        self.board.get_live_cells_in(region)
+    }
+
+    fn count_live_cells_in(&self, region: &Rectangle) -> u64 {
+        self.board.count_live_cells_in(region)
+    }
+
+    /// Observers are only told about the clear if there was something to clear
+    fn clear_region(&mut self, region: &Rectangle) {
+        let had_live_cells = self.board.count_live_cells_in(region) > 0;
+        self.board.clear_region(region);
+        if had_live_cells {
+            let region = self
+                .board
+                .get_bounds()
+                .map_or(*region, |bounds| bounds.intersection(region));
+            self.notify(BoardEvent::Cleared { region });
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::pattern::Pattern;
+    use crate::{DynamicVectorLifeBoard, FixedVectorLifeBoard};
+
+    /// The board wrapped with an observer that keeps every event it's told about, and the observer's id
+    fn logged_board(
+        board: Box<dyn LifeBoard>,
+    ) -> (ObservedBoard, Rc<RefCell<Vec<BoardEvent>>>, ObserverId) {
+        let mut board = ObservedBoard::new(board);
+        let events = Rc::new(RefCell::new(vec![]));
+        let log = Rc::clone(&events);
+        let id = board.add_observer(Box::new(move |_: &dyn LifeBoard, event: &BoardEvent| {
+            log.borrow_mut().push(*event)
+        }));
+        (board, events, id)
+    }
+
+    /// The events in cell order, for edits that don't promise which cell they change first
+    fn sorted(events: &[BoardEvent]) -> Vec<BoardEvent> {
+        let mut events = events.to_vec();
+        events.sort_by_key(|event| match event {
+            BoardEvent::CellChanged { cell, .. } => Some(*cell),
+            _ => None,
+        });
+        events
+    }
+
+    #[test]
+    pub fn edits_are_reported_once() {
+        let (mut board, events, _) = logged_board(Box::new(DynamicVectorLifeBoard::empty()));
+        board.set_live(1, 2);
+        board.set_live(1, 2);
+        board.set_liveness(5, 5, false);
+        board.set_liveness(1, 2, false);
+        assert_eq!(
+            vec![
+                BoardEvent::CellChanged {
+                    cell: LifePoint::new(1, 2),
+                    is_live: true
+                },
+                BoardEvent::CellChanged {
+                    cell: LifePoint::new(1, 2),
+                    is_live: false
+                },
+            ],
+            *events.borrow()
+        );
+    }
+
+    #[test]
+    pub fn steps_and_clears_are_reported() {
+        let (mut board, events, _) = logged_board(Box::new(DynamicVectorLifeBoard::empty()));
+        board.draw_pattern(&Pattern::BLINKER(), &LifePoint::ORIGIN);
+        events.borrow_mut().clear();
+
+        board.step_one();
+        board.step_one();
+        let region = Rectangle::new(-1, -1, 3, 3);
+        board.clear_region(&region);
+        board.clear_region(&region);
+
+        assert_eq!(
+            vec![
+                BoardEvent::Stepped { generation: 1 },
+                BoardEvent::Stepped { generation: 2 },
+                BoardEvent::Cleared { region },
+            ],
+            *events.borrow()
+        );
+        assert_eq!(2, board.get_generation());
+        assert!(board.get_live_cells().is_empty());
+    }
+
+    #[test]
+    pub fn observers_see_the_changed_board() {
+        let mut board = ObservedBoard::new(Box::new(DynamicVectorLifeBoard::empty()));
+        let populations = Rc::new(RefCell::new(vec![]));
+        let log = Rc::clone(&populations);
+        board.add_observer(Box::new(move |b: &dyn LifeBoard, _: &BoardEvent| {
+            log.borrow_mut().push(b.get_live_cells().len())
+        }));
+        board.draw_pattern(&Pattern::GLIDER_SOUTHEAST(), &LifePoint::ORIGIN);
+        assert_eq!(vec![1, 2, 3, 4, 5], *populations.borrow());
+    }
+
+    #[test]
+    pub fn removed_observers_hear_nothing() {
+        let (mut board, events, id) = logged_board(Box::new(DynamicVectorLifeBoard::empty()));
+        board.step_one();
+        assert_eq!("observers", board.get_stats().last().unwrap().0);
+
+        assert!(board.remove_observer(id).is_some());
+        assert!(board.remove_observer(id).is_none());
+        board.step_one();
+        assert_eq!(1, events.borrow().len());
+        assert_eq!(2, board.get_generation());
+    }
+
+    #[test]
+    pub fn region_edits_are_reported_cell_by_cell() {
+        let (mut board, events, _) = logged_board(Box::new(DynamicVectorLifeBoard::empty()));
+        board.fill_region(
+            &Rectangle::new(0, 0, 2, 1),
+            &Pattern::from_tuples(vec![(0, 0)]),
+        );
+        assert_eq!(
+            vec![
+                BoardEvent::CellChanged {
+                    cell: LifePoint::new(0, 0),
+                    is_live: true
+                },
+                BoardEvent::CellChanged {
+                    cell: LifePoint::new(1, 0),
+                    is_live: true
+                },
+            ],
+            sorted(&events.borrow())
+        );
+        events.borrow_mut().clear();
+
+        //Moving the pair one cell right only changes the cells at each end
+        board.move_region(&Rectangle::new(0, 0, 2, 1), &LifePoint::new(1, 0));
+        assert_eq!(
+            vec![
+                BoardEvent::CellChanged {
+                    cell: LifePoint::new(0, 0),
+                    is_live: false
+                },
+                BoardEvent::CellChanged {
+                    cell: LifePoint::new(2, 0),
+                    is_live: true
+                },
+            ],
+            sorted(&events.borrow())
+        );
+    }
+
+    #[test]
+    pub fn clears_are_reported_for_the_cells_on_the_board() {
+        let (mut board, events, _) = logged_board(Box::new(FixedVectorLifeBoard::with_size(8, 8)));
+        board.draw_pattern(&Pattern::BLOCK(), &LifePoint::ORIGIN);
+        events.borrow_mut().clear();
+
+        board.clear_region(&Rectangle::new(-5, -5, 7, 7));
+        assert_eq!(
+            vec![BoardEvent::Cleared {
+                region: Rectangle::new(0, 0, 2, 2)
+            }],
+            *events.borrow()
+        );
+    }
+}
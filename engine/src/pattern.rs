@@ -1,19 +1,21 @@
-use super::life_board::BoardPoint;
+use super::life_point::LifePoint;
 
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Pattern {
-    points: Vec<BoardPoint>,
+    points: Vec<LifePoint>,
 }
 
 impl Pattern {
-    pub fn new(points: Vec<BoardPoint>) -> Pattern {
+    pub fn new(points: Vec<LifePoint>) -> Pattern {
         Pattern { points }
     }
 
     pub fn from_tuples(points: Vec<(i64, i64)>) -> Pattern {
-        Pattern::new(points.iter().map(BoardPoint::from).collect())
+        Pattern::new(points.iter().map(LifePoint::from).collect())
     }
 
-    pub fn get_points(&self) -> &Vec<BoardPoint> {
+    pub fn get_points(&self) -> &Vec<LifePoint> {
         &self.points
     }
 
@@ -22,6 +24,63 @@ impl Pattern {
         Pattern::from_tuples(vec![(0, 0), (0, 1), (1, 0), (1, 1)])
     }
 
+    #[allow(non_snake_case)]
+    pub fn BLINKER() -> Pattern {
+        Pattern::from_tuples(vec![(-1, 0), (0, 0), (1, 0)])
+    }
+
+    #[allow(non_snake_case)]
+    pub fn TOAD() -> Pattern {
+        Pattern::from_tuples(vec![(-1, 0), (0, 0), (1, 0), (0, 1), (1, 1), (2, 1)])
+    }
+
+    #[allow(non_snake_case)]
+    pub fn BEEHIVE() -> Pattern {
+        Pattern::from_tuples(vec![(-1, -1), (0, -1), (-2, 0), (1, 0), (-1, 1), (0, 1)])
+    }
+
+    #[allow(non_snake_case)]
+    pub fn LOAF() -> Pattern {
+        Pattern::from_tuples(vec![
+            (0, -2),
+            (1, -2),
+            (-1, -1),
+            (2, -1),
+            (0, 0),
+            (2, 0),
+            (1, 1),
+        ])
+    }
+
+    #[allow(non_snake_case)]
+    pub fn BOAT() -> Pattern {
+        Pattern::from_tuples(vec![(-1, -1), (0, -1), (-1, 0), (1, 0), (0, 1)])
+    }
+
+    #[allow(non_snake_case)]
+    pub fn SHIP() -> Pattern {
+        Pattern::from_tuples(vec![(-1, -1), (0, -1), (-1, 0), (1, 0), (0, 1), (1, 1)])
+    }
+
+    #[allow(non_snake_case)]
+    pub fn TUB() -> Pattern {
+        Pattern::from_tuples(vec![(0, -1), (-1, 0), (1, 0), (0, 1)])
+    }
+
+    #[allow(non_snake_case)]
+    pub fn POND() -> Pattern {
+        Pattern::from_tuples(vec![
+            (0, -2),
+            (1, -2),
+            (-1, -1),
+            (2, -1),
+            (-1, 0),
+            (2, 0),
+            (0, 1),
+            (1, 1),
+        ])
+    }
+
     #[allow(non_snake_case)]
     pub fn BEACON() -> Pattern {
         Pattern::from_tuples(vec![
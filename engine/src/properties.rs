@@ -0,0 +1,310 @@
+//! Property-based tests: random soups and random edits, checked against a deliberately simple reference board.
+//! Like the conformance suite, every board in the registry is covered automatically.
+//! Each property runs `DEFAULT_CASES` cases, or as many as the `PROPTEST_CASES` environment variable asks for.
+
+use crate::life_board::LifeBoard;
+use crate::life_point::LifePoint;
+use crate::rectangle::Rectangle;
+use crate::registry::{available_boards, BoardInfo};
+use crate::snapshot::BoardSnapshot;
+use proptest::prelude::*;
+use std::collections::{HashMap, HashSet};
+
+/// Largest width and height of the area the random cells and edits go in
+const MAX_AREA_SIZE: i64 = 16;
+
+/// Cases run for each property when `PROPTEST_CASES` isn't set
+const DEFAULT_CASES: u32 = 64;
+
+/// How far from the center of an unbounded board the far-away areas go
+const FAR_AWAY: i64 = 1_000_000;
+
+/// The most obvious implementation of Life there is, to compare the real boards against.
+/// Cells outside `bounds` (if there are any) are always dead, like on the fixed boards.
+struct ReferenceBoard {
+    cells: HashSet<LifePoint>,
+    bounds: Option<Rectangle>,
+}
+
+impl ReferenceBoard {
+    fn new(bounds: Option<Rectangle>) -> ReferenceBoard {
+        ReferenceBoard {
+            cells: HashSet::new(),
+            bounds,
+        }
+    }
+
+    fn set_liveness(&mut self, cell: LifePoint, is_live: bool) {
+        if is_live {
+            self.cells.insert(cell);
+        } else {
+            self.cells.remove(&cell);
+        }
+    }
+
+    fn step_one(&mut self) {
+        let mut counts: HashMap<LifePoint, u8> = HashMap::new();
+        for cell in &self.cells {
+            for neighbor in cell.neighbors() {
+                *counts.entry(neighbor).or_default() += 1;
+            }
+        }
+        self.cells = counts
+            .into_iter()
+            .filter(|(cell, count)| *count == 3 || (*count == 2 && self.cells.contains(cell)))
+            .map(|(cell, _)| cell)
+            .filter(|cell| self.holds(cell))
+            .collect();
+    }
+
+    /// Whether the cell is on the board
+    fn holds(&self, cell: &LifePoint) -> bool {
+        match self.bounds {
+            Some(bounds) => bounds.contains(cell),
+            None => true,
+        }
+    }
+
+    fn count_live_neighbors(&self, cell: &LifePoint) -> u8 {
+        cell.neighbors().filter(|n| self.cells.contains(n)).count() as u8
+    }
+
+    fn snapshot(&self) -> BoardSnapshot {
+        BoardSnapshot::of_cells(self.cells.iter().copied().collect())
+    }
+}
+
+/// Where on each board the random cells go
+#[derive(Clone, Copy, Debug)]
+enum Anchor {
+    /// Around the board's center
+    Center,
+    /// Over the top-left corner of a fixed board, or across the origin of an unbounded one
+    NearCorner,
+    /// Over the bottom-right corner of a fixed board, or far from the origin of an unbounded one
+    FarCorner,
+}
+
+/// The square the random cells go in: `size` cells wide, placed by `anchor` and then moved by `shift`.
+/// Near the corners of a fixed board, some of it can be off the board.
+#[derive(Clone, Copy, Debug)]
+struct Placement {
+    size: i64,
+    anchor: Anchor,
+    shift: (i64, i64),
+}
+
+/// The area of this board that the placement covers
+fn area(info: &BoardInfo, placement: &Placement) -> Rectangle {
+    let size = placement.size;
+    let around = |point: LifePoint| point.offset(-size / 2, -size / 2);
+    let corner = match (placement.anchor, info.bounds) {
+        (Anchor::Center, _) => around(info.center),
+        (Anchor::NearCorner, Some(bounds)) => {
+            LifePoint::new(bounds.get_x_min(), bounds.get_y_min())
+        }
+        (Anchor::NearCorner, None) => around(LifePoint::ORIGIN),
+        (Anchor::FarCorner, Some(bounds)) => {
+            LifePoint::new(bounds.get_x_max() + 1 - size, bounds.get_y_max() + 1 - size)
+        }
+        (Anchor::FarCorner, None) => around(LifePoint::new(FAR_AWAY, -FAR_AWAY)),
+    };
+    let (dx, dy) = placement.shift;
+    Rectangle::new(corner.get_x() + dx, corner.get_y() + dy, size, size)
+}
+
+/// `PROPTEST_CASES` if it's set, otherwise `DEFAULT_CASES`
+fn config() -> ProptestConfig {
+    let mut config = ProptestConfig::default();
+    if std::env::var_os("PROPTEST_CASES").is_none() {
+        config.cases = DEFAULT_CASES;
+    }
+    config
+}
+
+/// Check the board has the same cells as the reference
+fn assert_same_cells(
+    board: &dyn LifeBoard,
+    reference: &ReferenceBoard,
+    info: &BoardInfo,
+    when: &str,
+) {
+    assert_eq!(
+        reference.snapshot(),
+        BoardSnapshot::of(board),
+        "{}: cells differ {}",
+        info.name,
+        when
+    );
+}
+
+/// Check every neighbor count the board reports in and around the area is right.
+/// This is what goes wrong first when a board keeps its counts up to date as cells change.
+fn assert_same_counts(
+    board: &dyn LifeBoard,
+    reference: &ReferenceBoard,
+    info: &BoardInfo,
+    placement: &Placement,
+    when: &str,
+) {
+    //Fixed boards can't answer for cells outside their bounds
+    let checked = area(info, placement).expanded(2);
+    let checked = info.bounds.map_or(checked, |b| b.intersection(&checked));
+    for cell in checked.points() {
+        assert_eq!(
+            reference.count_live_neighbors(&cell),
+            board.count_live_neighbors(cell.get_x(), cell.get_y()),
+            "{}: neighbor count of {} differs {}",
+            info.name,
+            cell,
+            when
+        );
+    }
+}
+
+/// Apply the edits to every board, then step them all, checking each one against the reference throughout.
+/// Edits are positions within the area, and whether to make the cell live.
+/// Edits off a fixed board have to fail without changing anything.
+fn check_edits(edits: &[(i64, i64, bool)], placement: &Placement, generations: u64) {
+    for info in available_boards() {
+        let origin = area(info, placement);
+        let mut board = info.create();
+        let mut reference = ReferenceBoard::new(info.bounds);
+        for (i, (x, y, is_live)) in edits.iter().enumerate() {
+            let cell = LifePoint::new(origin.get_x_min() + x, origin.get_y_min() + y);
+            let when = format!("after edit {}", i);
+            let on_board = reference.holds(&cell);
+            let result = board.try_set_liveness(cell.get_x(), cell.get_y(), *is_live);
+            assert_eq!(on_board, result.is_ok(), "{}: {} {}", info.name, cell, when);
+            if on_board {
+                reference.set_liveness(cell, *is_live);
+            }
+            assert_eq!(
+                *is_live && on_board,
+                board.is_live_point(&cell),
+                "{}: {} {}",
+                info.name,
+                cell,
+                when
+            );
+            assert_same_counts(board.as_ref(), &reference, info, placement, &when);
+        }
+        assert_same_cells(board.as_ref(), &reference, info, "after editing");
+        for generation in 1..=generations {
+            board.step_one();
+            reference.step_one();
+            let when = format!("at generation {}", generation);
+            assert_same_cells(board.as_ref(), &reference, info, &when);
+            assert_same_counts(board.as_ref(), &reference, info, placement, &when);
+        }
+    }
+}
+
+/// Edits that only ever change a cell's state: each cell is set live and dead alternately
+fn changing_edits(cells: Vec<(i64, i64)>) -> Vec<(i64, i64, bool)> {
+    let mut live = HashSet::new();
+    cells
+        .into_iter()
+        .map(|(x, y)| {
+            let is_live = live.insert((x, y));
+            if !is_live {
+                live.remove(&(x, y));
+            }
+            (x, y, is_live)
+        })
+        .collect()
+}
+
+fn placement() -> impl Strategy<Value = Placement> {
+    let anchor = prop_oneof![
+        Just(Anchor::Center),
+        Just(Anchor::NearCorner),
+        Just(Anchor::FarCorner),
+    ];
+    (1..=MAX_AREA_SIZE, anchor, -3i64..=3, -3i64..=3).prop_map(|(size, anchor, dx, dy)| Placement {
+        size,
+        anchor,
+        shift: (dx, dy),
+    })
+}
+
+/// A cell in the largest possible area, to be wrapped into the actual one
+fn cell_in_area() -> impl Strategy<Value = (i64, i64)> {
+    (0..MAX_AREA_SIZE, 0..MAX_AREA_SIZE)
+}
+
+/// Wrap edits made in the largest possible area into the placement's area
+fn wrapped(edits: Vec<(i64, i64, bool)>, placement: &Placement) -> Vec<(i64, i64, bool)> {
+    let size = placement.size;
+    edits
+        .into_iter()
+        .map(|(x, y, is_live)| (x % size, y % size, is_live))
+        .collect()
+}
+
+proptest! {
+    #![proptest_config(config())]
+
+    #[test]
+    fn random_soups_match_reference(
+        soup in proptest::collection::vec(any::<bool>(), (MAX_AREA_SIZE * MAX_AREA_SIZE) as usize),
+        placement in placement(),
+        generations in 0u64..24,
+    ) {
+        let size = placement.size;
+        let edits: Vec<(i64, i64, bool)> = soup
+            .iter()
+            .enumerate()
+            .map(|(i, is_live)| (i as i64 % MAX_AREA_SIZE, i as i64 / MAX_AREA_SIZE, *is_live))
+            .filter(|(x, y, is_live)| *is_live && *x < size && *y < size)
+            .collect();
+        check_edits(&edits, &placement, generations);
+    }
+
+    #[test]
+    fn changing_edits_match_reference(
+        cells in proptest::collection::vec(cell_in_area(), 0..60),
+        placement in placement(),
+        generations in 0u64..8,
+    ) {
+        let size = placement.size;
+        let cells = cells.into_iter().map(|(x, y)| (x % size, y % size)).collect();
+        check_edits(&changing_edits(cells), &placement, generations);
+    }
+
+    #[test]
+    fn repeated_edits_match_reference(
+        edits in proptest::collection::vec((cell_in_area(), any::<bool>()), 0..60),
+        placement in placement(),
+        generations in 0u64..8,
+    ) {
+        let edits = edits.into_iter().map(|((x, y), is_live)| (x, y, is_live)).collect();
+        check_edits(&wrapped(edits, &placement), &placement, generations);
+    }
+}
+
+#[test]
+pub fn reference_board_runs_a_blinker() {
+    let mut reference = ReferenceBoard::new(None);
+    for x in -1..=1 {
+        reference.set_liveness(LifePoint::new(x, 0), true);
+    }
+    reference.step_one();
+    let expected = BoardSnapshot::of_cells(vec![
+        LifePoint::new(0, -1),
+        LifePoint::new(0, 0),
+        LifePoint::new(0, 1),
+    ]);
+    assert_eq!(expected, reference.snapshot());
+}
+
+#[test]
+pub fn reference_board_respects_bounds() {
+    let mut reference = ReferenceBoard::new(Some(Rectangle::new(0, 0, 3, 3)));
+    for y in 0..3 {
+        reference.set_liveness(LifePoint::new(0, y), true);
+    }
+    reference.step_one();
+    let expected = BoardSnapshot::of_cells(vec![LifePoint::new(0, 1), LifePoint::new(1, 1)]);
+    assert_eq!(expected, reference.snapshot());
+}
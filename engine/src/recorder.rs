@@ -0,0 +1,250 @@
+//! Per-generation statistics for a running board, for plotting how a pattern develops over time.
+
+use crate::life_board::LifeBoard;
+use crate::observer::{BoardEvent, BoardObserver};
+use crate::snapshot::BoardSnapshot;
+
+/// Statistics for one generation of a board
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GenerationRecord {
+    pub generation: u64,
+    pub population: u64,
+    /// Width of the box containing every live cell, or 0 if there aren't any
+    pub width: u64,
+    /// Height of the box containing every live cell, or 0 if there aren't any
+    pub height: u64,
+    /// Cells that are live in this generation but weren't in the previous one
+    pub births: u64,
+    /// Cells that were live in the previous generation but aren't in this one
+    pub deaths: u64,
+}
+
+/// A sequence of generation records, in the order they were taken
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TimeSeries {
+    records: Vec<GenerationRecord>,
+}
+
+impl TimeSeries {
+    pub fn get_records(&self) -> &Vec<GenerationRecord> {
+        &self.records
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Export as CSV, with a header row
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("generation,population,width,height,births,deaths\n");
+        for r in &self.records {
+            csv.push_str(&format!(
+                "{},{},{},{},{},{}\n",
+                r.generation, r.population, r.width, r.height, r.births, r.deaths
+            ));
+        }
+        csv
+    }
+
+    /// Export as a JSON array with one object per generation
+    pub fn to_json(&self) -> String {
+        let rows: Vec<String> = self
+            .records
+            .iter()
+            .map(|r| {
+                format!(
+                    "{{\"generation\":{},\"population\":{},\"width\":{},\"height\":{},\"births\":{},\"deaths\":{}}}",
+                    r.generation, r.population, r.width, r.height, r.births, r.deaths
+                )
+            })
+            .collect();
+        format!("[{}]", rows.join(","))
+    }
+}
+
+/// Builds up a `TimeSeries` by being shown the board once per generation.
+/// The first board recorded is generation 0, and counts every live cell as a birth.
+#[derive(Default)]
+pub struct Recorder {
+    previous: Option<BoardSnapshot>,
+    series: TimeSeries,
+}
+
+impl Recorder {
+    pub fn new() -> Recorder {
+        Recorder::default()
+    }
+
+    pub fn record(&mut self, board: &dyn LifeBoard) {
+        let current = BoardSnapshot::of(board);
+        let generation = self.series.records.len() as u64;
+        self.series.records.push(generation_record(
+            generation,
+            self.previous.as_ref(),
+            &current,
+        ));
+        self.previous = Some(current);
+    }
+
+    pub fn get_series(&self) -> &TimeSeries {
+        &self.series
+    }
+
+    pub fn into_series(self) -> TimeSeries {
+        self.series
+    }
+}
+
+/// A recorder observes a board by recording it after every step
+impl BoardObserver for Recorder {
+    fn on_event(&mut self, board: &dyn LifeBoard, event: &BoardEvent) {
+        if let BoardEvent::Stepped { .. } = event {
+            self.record(board);
+        }
+    }
+}
+
+/// Record the board as it is now, then after each of the next `generations` steps
+pub fn record_generations(board: &mut dyn LifeBoard, generations: u64) -> TimeSeries {
+    let mut recorder = Recorder::new();
+    recorder.record(board);
+    for _ in 0..generations {
+        board.step_one();
+        recorder.record(board);
+    }
+    recorder.into_series()
+}
+
+/// Statistics for `current`, compared against the generation before it if there was one
+pub(crate) fn generation_record(
+    generation: u64,
+    previous: Option<&BoardSnapshot>,
+    current: &BoardSnapshot,
+) -> GenerationRecord {
+    let (births, deaths) = match previous {
+        Some(previous) => {
+            let (births, deaths) = current.changes_since(previous);
+            (births.len() as u64, deaths.len() as u64)
+        }
+        None => (current.population() as u64, 0),
+    };
+    let (width, height) = match current.get_bounds() {
+        Some((min, max)) => (
+            (max.get_x() - min.get_x() + 1) as u64,
+            (max.get_y() - min.get_y() + 1) as u64,
+        ),
+        None => (0, 0),
+    };
+    GenerationRecord {
+        generation,
+        population: current.population() as u64,
+        width,
+        height,
+        births,
+        deaths,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::life_point::LifePoint;
+    use crate::observer::ObservedBoard;
+    use crate::pattern::Pattern;
+    use crate::{DynamicArray2dLifeBoard, DynamicVectorLifeBoard};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    fn blinker_series() -> TimeSeries {
+        let mut board = DynamicArray2dLifeBoard::empty();
+        board.draw_pattern(&Pattern::BLINKER(), &LifePoint::new(0, 0));
+        record_generations(&mut board, 2)
+    }
+
+    #[test]
+    pub fn blinker_is_recorded() {
+        let series = blinker_series();
+        assert_eq!(3, series.len());
+
+        let first = series.get_records()[0];
+        assert_eq!(3, first.population);
+        assert_eq!(3, first.births);
+        assert_eq!(0, first.deaths);
+
+        //Each flip kills the two end cells and births two new ones
+        for record in &series.get_records()[1..] {
+            assert_eq!(3, record.population);
+            assert_eq!(2, record.births);
+            assert_eq!(2, record.deaths);
+        }
+        let records = series.get_records();
+        assert_eq!((3, 1), (records[0].width, records[0].height));
+        assert_eq!((1, 3), (records[1].width, records[1].height));
+    }
+
+    #[test]
+    pub fn empty_board_has_zero_size() {
+        let mut board = DynamicArray2dLifeBoard::empty();
+        let series = record_generations(&mut board, 1);
+        assert_eq!(0, series.get_records()[1].width);
+        assert_eq!(0, series.get_records()[1].population);
+    }
+
+    #[test]
+    pub fn csv_export_works() {
+        let csv = blinker_series().to_csv();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!("generation,population,width,height,births,deaths", lines[0]);
+        assert_eq!("0,3,3,1,3,0", lines[1]);
+        assert_eq!("1,3,1,3,2,2", lines[2]);
+        assert_eq!(4, lines.len());
+    }
+
+    #[test]
+    pub fn json_export_works() {
+        let json = blinker_series().to_json();
+        assert!(json.starts_with(
+            "[{\"generation\":0,\"population\":3,\"width\":3,\"height\":1,\"births\":3,\"deaths\":0},"
+        ));
+        assert!(json.ends_with("}]"));
+        assert_eq!("[]", TimeSeries::default().to_json());
+    }
+
+    #[test]
+    pub fn recorder_records_each_step() {
+        let mut inner = DynamicVectorLifeBoard::empty();
+        inner.draw_pattern(&Pattern::BLINKER(), &LifePoint::ORIGIN);
+        let mut recorder = Recorder::new();
+        recorder.on_event(&inner, &BoardEvent::Stepped { generation: 1 });
+        recorder.on_event(
+            &inner,
+            &BoardEvent::CellChanged {
+                cell: LifePoint::ORIGIN,
+                is_live: true,
+            },
+        );
+        recorder.on_event(&inner, &BoardEvent::Stepped { generation: 2 });
+        assert_eq!(2, recorder.get_series().len());
+    }
+
+    #[test]
+    pub fn shared_recorders_can_still_be_read() {
+        let recorder = Rc::new(RefCell::new(Recorder::new()));
+        let mut board = ObservedBoard::new(Box::new(DynamicVectorLifeBoard::empty()));
+        board.add_observer(Box::new(Rc::clone(&recorder)));
+        board.draw_pattern(&Pattern::BLINKER(), &LifePoint::ORIGIN);
+        board.step_one();
+        board.step_one();
+        assert_eq!(2, recorder.borrow().get_series().len());
+        assert_eq!(
+            3,
+            recorder.borrow().get_series().get_records()[1].population
+        );
+    }
+}
@@ -0,0 +1,363 @@
+#[cfg(feature = "serde")]
+use crate::error::EngineError;
+use crate::life_point::LifePoint;
+use std::cmp::{max, min};
+use std::fmt::Display;
+use std::hash::{Hash, Hasher};
+use std::ops::Range;
+
+type BoardIndex = i64;
+type GridIndex = usize;
+
+/// An axis-aligned block of cells, given by its top-left corner and size.
+/// Any rectangle with no width or no height is empty, and all empty rectangles are equal.
+#[derive(Copy, Clone, Debug, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "RectangleFields")
+)]
+pub struct Rectangle {
+    x_min: BoardIndex,
+    y_min: BoardIndex,
+    width: BoardIndex,
+    height: BoardIndex,
+}
+
+impl Rectangle {
+    pub const fn new(
+        x_min: BoardIndex,
+        y_min: BoardIndex,
+        width: BoardIndex,
+        height: BoardIndex,
+    ) -> Rectangle {
+        if width <= 0 || height <= 0 {
+            return Rectangle::empty();
+        }
+        Rectangle {
+            x_min,
+            y_min,
+            width,
+            height,
+        }
+    }
+
+    /// The rectangle between two corners, inclusive of both
+    pub fn from_corners(first: LifePoint, second: LifePoint) -> Rectangle {
+        let x_min = min(first.get_x(), second.get_x());
+        let y_min = min(first.get_y(), second.get_y());
+        let x_max = max(first.get_x(), second.get_x());
+        let y_max = max(first.get_y(), second.get_y());
+        Rectangle::new(x_min, y_min, x_max - x_min + 1, y_max - y_min + 1)
+    }
+
+    /// The smallest rectangle containing all of the points
+    pub fn from_points<'a>(points: impl IntoIterator<Item = &'a LifePoint>) -> Rectangle {
+        let mut rect = Rectangle::empty();
+        for point in points {
+            rect.expand_to_include(point.get_x(), point.get_y());
+        }
+        rect
+    }
+
+    pub const fn empty() -> Rectangle {
+        Rectangle {
+            x_min: 0,
+            width: 0,
+            y_min: 0,
+            height: 0,
+        }
+    }
+
+    pub fn get_x_min(&self) -> BoardIndex {
+        self.x_min
+    }
+
+    pub fn get_y_min(&self) -> BoardIndex {
+        self.y_min
+    }
+
+    pub fn get_width(&self) -> BoardIndex {
+        self.width
+    }
+
+    pub fn get_height(&self) -> BoardIndex {
+        self.height
+    }
+
+    pub fn get_x_max(&self) -> BoardIndex {
+        self.x_min + self.width - 1
+    }
+
+    pub fn get_y_max(&self) -> BoardIndex {
+        self.y_min + self.height - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.width <= 0 || self.height <= 0
+    }
+
+    /// The number of cells inside the rectangle
+    pub fn area(&self) -> u64 {
+        if self.is_empty() {
+            0
+        } else {
+            (self.width as u64) * (self.height as u64)
+        }
+    }
+
+    pub fn contains_point(&self, x: BoardIndex, y: BoardIndex) -> bool {
+        x >= self.x_min && x <= self.get_x_max() && y >= self.y_min && y <= self.get_y_max()
+    }
+
+    pub fn contains(&self, point: &LifePoint) -> bool {
+        self.contains_point(point.get_x(), point.get_y())
+    }
+
+    pub fn expand_to_include(&mut self, x: BoardIndex, y: BoardIndex) {
+        if self.is_empty() {
+            self.x_min = x;
+            self.y_min = y;
+            self.width = 1;
+            self.height = 1;
+        } else {
+            let x_max = max(self.x_min + self.width - 1, x);
+            let y_max = max(self.y_min + self.height - 1, y);
+            self.x_min = min(self.x_min, x);
+            self.width = x_max - self.x_min + 1;
+            self.y_min = min(self.y_min, y);
+            self.height = y_max - self.y_min + 1;
+        }
+    }
+
+    /// Grow the rectangle by `by` cells on every side, or shrink it if `by` is negative
+    pub fn expanded(&self, by: BoardIndex) -> Rectangle {
+        if self.is_empty() {
+            return *self;
+        }
+        Rectangle::new(
+            self.x_min - by,
+            self.y_min - by,
+            self.width + 2 * by,
+            self.height + 2 * by,
+        )
+    }
+
+    /// The cells that are in both rectangles
+    pub fn intersection(&self, other: &Rectangle) -> Rectangle {
+        if self.is_empty() || other.is_empty() {
+            return Rectangle::empty();
+        }
+        let x_min = max(self.x_min, other.x_min);
+        let y_min = max(self.y_min, other.y_min);
+        let x_max = min(self.get_x_max(), other.get_x_max());
+        let y_max = min(self.get_y_max(), other.get_y_max());
+        Rectangle::new(x_min, y_min, x_max - x_min + 1, y_max - y_min + 1)
+    }
+
+    /// The smallest rectangle containing both rectangles
+    pub fn union(&self, other: &Rectangle) -> Rectangle {
+        if self.is_empty() {
+            return *other;
+        }
+        if other.is_empty() {
+            return *self;
+        }
+        let x_min = min(self.x_min, other.x_min);
+        let y_min = min(self.y_min, other.y_min);
+        let x_max = max(self.get_x_max(), other.get_x_max());
+        let y_max = max(self.get_y_max(), other.get_y_max());
+        Rectangle::new(x_min, y_min, x_max - x_min + 1, y_max - y_min + 1)
+    }
+
+    pub fn x_range(&self) -> Range<BoardIndex> {
+        if self.is_empty() {
+            return 0..0;
+        }
+        self.x_min..(self.x_min + self.width)
+    }
+
+    pub fn y_range(&self) -> Range<BoardIndex> {
+        if self.is_empty() {
+            return 0..0;
+        }
+        self.y_min..(self.y_min + self.height)
+    }
+
+    /// Every point in the rectangle, row by row from the top-left corner
+    pub fn points(&self) -> impl Iterator<Item = LifePoint> {
+        let x_range = self.x_range();
+        self.y_range()
+            .flat_map(move |y| x_range.clone().map(move |x| LifePoint::new(x, y)))
+    }
+
+    fn hash_fields<H: Hasher>(&self, state: &mut H) {
+        (self.x_min, self.y_min, self.width, self.height).hash(state);
+    }
+
+    pub(crate) fn to_grid_x(self, x: BoardIndex) -> GridIndex {
+        (x - self.x_min) as GridIndex
+    }
+
+    pub(crate) fn to_grid_y(self, y: BoardIndex) -> GridIndex {
+        (y - self.y_min) as GridIndex
+    }
+
+    pub(crate) fn to_grid_point(self, x: BoardIndex, y: BoardIndex) -> (GridIndex, GridIndex) {
+        (self.to_grid_x(x), self.to_grid_y(y))
+    }
+}
+
+impl PartialEq for Rectangle {
+    fn eq(&self, other: &Rectangle) -> bool {
+        (self.is_empty() && other.is_empty())
+            || (self.x_min == other.x_min
+                && self.y_min == other.y_min
+                && self.width == other.width
+                && self.height == other.height)
+    }
+}
+
+impl Hash for Rectangle {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        //Must agree with eq, so every empty rectangle hashes the same
+        if self.is_empty() {
+            Rectangle::empty().hash_fields(state);
+        } else {
+            self.hash_fields(state);
+        }
+    }
+}
+
+/// A rectangle as it's serialized, which is only turned into a `Rectangle` if it makes sense as one
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct RectangleFields {
+    x_min: BoardIndex,
+    y_min: BoardIndex,
+    width: BoardIndex,
+    height: BoardIndex,
+}
+
+/// Sizes can't be negative, and the far edges have to be valid coordinates
+#[cfg(feature = "serde")]
+impl TryFrom<RectangleFields> for Rectangle {
+    type Error = EngineError;
+
+    fn try_from(fields: RectangleFields) -> Result<Rectangle, EngineError> {
+        let RectangleFields {
+            x_min,
+            y_min,
+            width,
+            height,
+        } = fields;
+        let fits = width >= 0
+            && height >= 0
+            && x_min.checked_add(width).is_some()
+            && y_min.checked_add(height).is_some();
+        if fits {
+            Ok(Rectangle::new(x_min, y_min, width, height))
+        } else {
+            Err(EngineError::InvalidRectangle {
+                x_min,
+                y_min,
+                width,
+                height,
+            })
+        }
+    }
+}
+
+impl Display for Rectangle {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
+        write!(
+            fmt,
+            "(minX:{}, minY:{}, width:{}, height:{})",
+            self.x_min, self.y_min, self.width, self.height
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn corners_are_inclusive() {
+        let rect = Rectangle::from_corners(LifePoint::new(3, 4), LifePoint::new(-1, 2));
+        assert_eq!(Rectangle::new(-1, 2, 5, 3), rect);
+        assert_eq!((3, 4), (rect.get_x_max(), rect.get_y_max()));
+        assert_eq!(15, rect.area());
+    }
+
+    #[test]
+    pub fn contains_works() {
+        let rect = Rectangle::new(0, 0, 2, 2);
+        assert!(rect.contains(&LifePoint::new(1, 1)));
+        assert!(!rect.contains(&LifePoint::new(2, 1)));
+        assert!(!Rectangle::empty().contains(&LifePoint::ORIGIN));
+    }
+
+    #[test]
+    pub fn intersection_works() {
+        let a = Rectangle::new(0, 0, 4, 4);
+        let b = Rectangle::new(2, -1, 4, 2);
+        assert_eq!(Rectangle::new(2, 0, 2, 1), a.intersection(&b));
+        assert_eq!(a.intersection(&b), b.intersection(&a));
+        assert!(a.intersection(&Rectangle::new(10, 10, 1, 1)).is_empty());
+        assert!(a.intersection(&Rectangle::empty()).is_empty());
+    }
+
+    #[test]
+    pub fn union_works() {
+        let a = Rectangle::new(0, 0, 1, 1);
+        let b = Rectangle::new(4, -2, 1, 1);
+        assert_eq!(Rectangle::new(0, -2, 5, 3), a.union(&b));
+        assert_eq!(a, a.union(&Rectangle::empty()));
+        assert_eq!(b, Rectangle::empty().union(&b));
+    }
+
+    #[test]
+    pub fn points_are_row_by_row() {
+        let points: Vec<LifePoint> = Rectangle::new(5, 5, 2, 2).points().collect();
+        assert_eq!(
+            vec![
+                LifePoint::new(5, 5),
+                LifePoint::new(6, 5),
+                LifePoint::new(5, 6),
+                LifePoint::new(6, 6)
+            ],
+            points
+        );
+        assert_eq!(0, Rectangle::empty().points().count());
+    }
+
+    #[test]
+    pub fn expanded_works() {
+        assert_eq!(
+            Rectangle::new(-1, -1, 3, 3),
+            Rectangle::new(0, 0, 1, 1).expanded(1)
+        );
+        assert!(Rectangle::new(0, 0, 2, 2).expanded(-1).is_empty());
+        assert!(Rectangle::empty().expanded(3).is_empty());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    pub fn deserializing_checks_the_size() {
+        let rect = Rectangle::new(-3, 4, 5, 6);
+        let json = serde_json::to_string(&rect).unwrap();
+        assert_eq!("{\"x_min\":-3,\"y_min\":4,\"width\":5,\"height\":6}", json);
+        assert_eq!(rect, serde_json::from_str(&json).unwrap());
+
+        let empty: Rectangle =
+            serde_json::from_str("{\"x_min\":7,\"y_min\":7,\"width\":0,\"height\":3}").unwrap();
+        assert!(empty.is_empty());
+        for json in [
+            "{\"x_min\":0,\"y_min\":0,\"width\":-1,\"height\":3}",
+            "{\"x_min\":9223372036854775807,\"y_min\":0,\"width\":2,\"height\":3}",
+        ] {
+            assert!(serde_json::from_str::<Rectangle>(json).is_err());
+        }
+    }
+}
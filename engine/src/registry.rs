@@ -0,0 +1,154 @@
+//! Every `LifeBoard` implementation, listed by name so that programs can let the user pick one at runtime.
+
+use crate::error::EngineError;
+use crate::life_board::LifeBoard;
+use crate::life_point::LifePoint;
+use crate::rectangle::Rectangle;
+use crate::{
+    DynamicArray2dLifeBoard, DynamicVectorLifeBoard, FixedBitfieldLifeBoard, FixedVectorLifeBoard,
+};
+
+/// A board implementation that can be created by name
+pub struct BoardInfo {
+    /// The name used to pick this implementation, eg on the command line
+    pub name: &'static str,
+    /// Other names that are accepted for this implementation
+    pub aliases: &'static [&'static str],
+    pub description: &'static str,
+    /// A good place to draw a pattern so it has room to grow in every direction
+    pub center: LifePoint,
+    /// The cells the board can hold, or `None` if it grows to fit any coordinates
+    pub bounds: Option<Rectangle>,
+    constructor: fn() -> Box<dyn LifeBoard>,
+}
+
+impl BoardInfo {
+    /// Create a new, empty board of this kind
+    pub fn create(&self) -> Box<dyn LifeBoard> {
+        (self.constructor)()
+    }
+
+    fn is_called(&self, name: &str) -> bool {
+        self.name.eq_ignore_ascii_case(name)
+            || self.aliases.iter().any(|a| a.eq_ignore_ascii_case(name))
+    }
+}
+
+/// The fixed boards are created at their default size
+const FIXED_BOUNDS: Rectangle = Rectangle::new(0, 0, 256, 256);
+
+const BOARDS: &[BoardInfo] = &[
+    BoardInfo {
+        name: "fixed-vector",
+        aliases: &[],
+        description: "256x256 cells stored as nested vectors of booleans",
+        center: LifePoint::new(128, 128),
+        bounds: Some(FIXED_BOUNDS),
+        constructor: new_fixed_vector,
+    },
+    BoardInfo {
+        name: "fixed-bitfield",
+        aliases: &["bitfield"],
+        description: "256x256 cells, each a bitfield holding its own neighbor count",
+        center: LifePoint::new(128, 128),
+        bounds: Some(FIXED_BOUNDS),
+        constructor: new_fixed_bitfield,
+    },
+    BoardInfo {
+        name: "dynamic-vector",
+        aliases: &[],
+        description: "Unbounded, grows nested vectors to fit the live cells",
+        center: LifePoint::ORIGIN,
+        bounds: None,
+        constructor: new_dynamic_vector,
+    },
+    BoardInfo {
+        name: "dynamic-array2d",
+        aliases: &["array2d"],
+        description: "Unbounded, grows a flat 2D array to fit the live cells",
+        center: LifePoint::ORIGIN,
+        bounds: None,
+        constructor: new_dynamic_array2d,
+    },
+];
+
+fn new_fixed_vector() -> Box<dyn LifeBoard> {
+    Box::new(FixedVectorLifeBoard::empty())
+}
+
+fn new_fixed_bitfield() -> Box<dyn LifeBoard> {
+    Box::new(FixedBitfieldLifeBoard::empty())
+}
+
+fn new_dynamic_vector() -> Box<dyn LifeBoard> {
+    Box::new(DynamicVectorLifeBoard::empty())
+}
+
+fn new_dynamic_array2d() -> Box<dyn LifeBoard> {
+    Box::new(DynamicArray2dLifeBoard::empty())
+}
+
+/// Every board implementation, in a fixed order
+pub fn available_boards() -> &'static [BoardInfo] {
+    BOARDS
+}
+
+/// Look up an implementation by its name or one of its aliases, ignoring case
+pub fn find_board(name: &str) -> Result<&'static BoardInfo, EngineError> {
+    BOARDS
+        .iter()
+        .find(|info| info.is_called(name))
+        .ok_or_else(|| EngineError::UnknownBoard(name.to_owned()))
+}
+
+/// Create a new, empty board of the named implementation
+pub fn create_board(name: &str) -> Result<Box<dyn LifeBoard>, EngineError> {
+    find_board(name).map(BoardInfo::create)
+}
+
+/// The names of every implementation, for usage messages
+pub fn board_names() -> Vec<&'static str> {
+    BOARDS.iter().map(|info| info.name).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::pattern::Pattern;
+
+    #[test]
+    pub fn every_board_can_be_created() {
+        for info in available_boards() {
+            let mut board = create_board(info.name).unwrap();
+            board.draw_pattern(&Pattern::BLINKER(), &info.center);
+            board.step_one();
+            assert_eq!(3, board.get_live_cells().len(), "{}", info.name);
+        }
+    }
+
+    #[test]
+    pub fn names_and_aliases_are_unique() {
+        let mut names: Vec<String> = available_boards()
+            .iter()
+            .flat_map(|info| info.aliases.iter().chain([&info.name]))
+            .map(|name| name.to_ascii_lowercase())
+            .collect();
+        let count = names.len();
+        names.sort();
+        names.dedup();
+        assert_eq!(count, names.len());
+    }
+
+    #[test]
+    pub fn lookup_ignores_case_and_accepts_aliases() {
+        assert_eq!("fixed-bitfield", find_board("Bitfield").unwrap().name);
+        assert_eq!(
+            "dynamic-array2d",
+            find_board("DYNAMIC-ARRAY2D").unwrap().name
+        );
+        assert_eq!(
+            Some(EngineError::UnknownBoard("hashlife".to_owned())),
+            create_board("hashlife").err()
+        );
+    }
+}
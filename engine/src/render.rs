@@ -0,0 +1,359 @@
+//! Still images of a board, for reports and anywhere else there's no terminal to draw in.
+//! Rendering only goes through the `LifeBoard` trait, so every implementation can be rendered the same way.
+
+use crate::error::{check_image_size, EngineError};
+use crate::life_board::LifeBoard;
+use crate::life_point::LifePoint;
+use crate::snapshot::BoardSnapshot;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Color {
+    pub const BLACK: Color = Color::new(0, 0, 0);
+    pub const WHITE: Color = Color::new(255, 255, 255);
+    pub const GRAY: Color = Color::new(192, 192, 192);
+
+    pub const fn new(r: u8, g: u8, b: u8) -> Color {
+        Color { r, g, b }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Palette {
+    pub live: Color,
+    pub dead: Color,
+    pub grid: Color,
+}
+
+impl Default for Palette {
+    /// Black cells on white, with light gray grid lines
+    fn default() -> Palette {
+        Palette {
+            live: Color::BLACK,
+            dead: Color::WHITE,
+            grid: Color::GRAY,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RenderOptions {
+    /// Width and height of each cell in pixels, not counting grid lines
+    pub cell_size: u32,
+    /// Draw a one pixel line between cells and around the edge
+    pub grid_lines: bool,
+    pub palette: Palette,
+    /// Number of blank cells to leave around the live cells when no region is given
+    pub margin: i64,
+}
+
+impl Default for RenderOptions {
+    fn default() -> RenderOptions {
+        RenderOptions {
+            cell_size: 4,
+            grid_lines: false,
+            palette: Palette::default(),
+            margin: 1,
+        }
+    }
+}
+
+/// An RGB image, stored row by row from the top-left corner
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Image {
+    width: u32,
+    height: u32,
+    pixels: Vec<Color>,
+}
+
+impl Image {
+    fn filled(width: u32, height: u32, color: Color) -> Image {
+        Image {
+            width,
+            height,
+            pixels: vec![color; (width * height) as usize],
+        }
+    }
+
+    pub fn get_width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn get_height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn get_pixel(&self, x: u32, y: u32) -> Color {
+        self.pixels[(y * self.width + x) as usize]
+    }
+
+    fn fill_rect(&mut self, x: u32, y: u32, width: u32, height: u32, color: Color) {
+        for yi in y..y + height {
+            let row = (yi * self.width) as usize;
+            self.pixels[row + x as usize..row + (x + width) as usize].fill(color);
+        }
+    }
+
+    /// The pixels as packed RGB bytes
+    pub fn to_rgb_bytes(&self) -> Vec<u8> {
+        self.pixels.iter().flat_map(|c| [c.r, c.g, c.b]).collect()
+    }
+
+    pub fn write_png<W: Write>(&self, writer: W) -> io::Result<()> {
+        let mut encoder = png::Encoder::new(writer, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut png_writer = encoder.write_header().map_err(io::Error::other)?;
+        png_writer
+            .write_image_data(&self.to_rgb_bytes())
+            .map_err(io::Error::other)
+    }
+
+    pub fn to_png(&self) -> io::Result<Vec<u8>> {
+        let mut png = vec![];
+        self.write_png(&mut png)?;
+        Ok(png)
+    }
+
+    pub fn save_png(&self, path: &Path) -> io::Result<()> {
+        self.write_png(BufWriter::new(File::create(path)?))
+    }
+}
+
+/// Work out which cells to draw: the given corners (inclusive), or the live cells plus the margin.
+/// An empty board with no region given draws just the margin around the origin.
+pub(crate) fn resolve_region(
+    snapshot: &BoardSnapshot,
+    region: Option<(LifePoint, LifePoint)>,
+    margin: i64,
+) -> (LifePoint, LifePoint) {
+    if let Some(region) = region {
+        return region;
+    }
+    let (min, max) = snapshot
+        .get_bounds()
+        .unwrap_or((LifePoint::new(0, 0), LifePoint::new(0, 0)));
+    (min.offset(-margin, -margin), max.offset(margin, margin))
+}
+
+/// Draw the region of the board given by its top-left and bottom-right corners (inclusive),
+/// or the live cells plus the margin in `options` if no region is given
+pub fn render(
+    board: &dyn LifeBoard,
+    region: Option<(LifePoint, LifePoint)>,
+    options: &RenderOptions,
+) -> Result<Image, EngineError> {
+    render_snapshot(&BoardSnapshot::of(board), region, options)
+}
+
+/// The width and height in pixels of an image of the region between the corners (inclusive).
+/// Fails if the image would have no pixels, or more than `MAX_IMAGE_PIXELS`.
+pub fn image_size(
+    region: (LifePoint, LifePoint),
+    options: &RenderOptions,
+) -> Result<(u32, u32), EngineError> {
+    //Worked out in i128, where the sides can't overflow whatever the region and cell size
+    let grid = options.grid_lines as i128;
+    let pitch = options.cell_size as i128 + grid;
+    let side = |first: i64, last: i64| (last as i128 - first as i128 + 1).max(0) * pitch + grid;
+    let (min, max) = region;
+    let (width, height) = (
+        side(min.get_x(), max.get_x()),
+        side(min.get_y(), max.get_y()),
+    );
+    let (width, height) = check_image_size(width, height)?;
+    Ok((width as u32, height as u32))
+}
+
+/// Draw a snapshot in the same way as `render`
+pub fn render_snapshot(
+    snapshot: &BoardSnapshot,
+    region: Option<(LifePoint, LifePoint)>,
+    options: &RenderOptions,
+) -> Result<Image, EngineError> {
+    let (min, max) = resolve_region(snapshot, region, options.margin);
+    let (width, height) = image_size((min, max), options)?;
+
+    let grid = options.grid_lines as u32;
+    let pitch = options.cell_size + grid;
+    let (columns, rows) = ((width - grid) / pitch, (height - grid) / pitch);
+    let background = if options.grid_lines {
+        options.palette.grid
+    } else {
+        options.palette.dead
+    };
+    let mut image = Image::filled(width, height, background);
+
+    if options.grid_lines {
+        for row in 0..rows {
+            for column in 0..columns {
+                let (x, y) = (column * pitch + grid, row * pitch + grid);
+                image.fill_rect(
+                    x,
+                    y,
+                    options.cell_size,
+                    options.cell_size,
+                    options.palette.dead,
+                );
+            }
+        }
+    }
+
+    let is_inside = |cell: &LifePoint| {
+        (min.get_x()..=max.get_x()).contains(&cell.get_x())
+            && (min.get_y()..=max.get_y()).contains(&cell.get_y())
+    };
+    for cell in snapshot.get_cells().iter().filter(|cell| is_inside(cell)) {
+        let column = (cell.get_x() - min.get_x()) as u32;
+        let row = (cell.get_y() - min.get_y()) as u32;
+        image.fill_rect(
+            column * pitch + grid,
+            row * pitch + grid,
+            options.cell_size,
+            options.cell_size,
+            options.palette.live,
+        );
+    }
+
+    Ok(image)
+}
+
+/// Render the board and save it as a PNG file
+pub fn save_png(
+    board: &dyn LifeBoard,
+    region: Option<(LifePoint, LifePoint)>,
+    options: &RenderOptions,
+    path: &Path,
+) -> io::Result<()> {
+    render(board, region, options)?.save_png(path)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::pattern::Pattern;
+    use crate::{DynamicVectorLifeBoard, FixedBitfieldLifeBoard};
+
+    fn options(cell_size: u32, grid_lines: bool, margin: i64) -> RenderOptions {
+        RenderOptions {
+            cell_size,
+            grid_lines,
+            margin,
+            ..RenderOptions::default()
+        }
+    }
+
+    #[test]
+    pub fn live_bounds_are_rendered() {
+        let mut board = DynamicVectorLifeBoard::empty();
+        board.draw_pattern(&Pattern::BLINKER(), &LifePoint::new(0, 0));
+        let image = render(&board, None, &options(2, false, 1)).unwrap();
+
+        //3x1 cells plus a margin of 1 all round, at 2 pixels per cell
+        assert_eq!((10, 6), (image.get_width(), image.get_height()));
+        assert_eq!(Color::WHITE, image.get_pixel(0, 0));
+        assert_eq!(Color::BLACK, image.get_pixel(2, 2));
+        assert_eq!(Color::BLACK, image.get_pixel(7, 3));
+        assert_eq!(Color::WHITE, image.get_pixel(8, 3));
+    }
+
+    #[test]
+    pub fn grid_lines_are_drawn() {
+        let mut board = FixedBitfieldLifeBoard::empty();
+        board.set_live(5, 5);
+        let region = (LifePoint::new(5, 5), LifePoint::new(6, 5));
+        let image = render(&board, Some(region), &options(3, true, 0)).unwrap();
+
+        assert_eq!((9, 5), (image.get_width(), image.get_height()));
+        assert_eq!(Color::GRAY, image.get_pixel(0, 0));
+        assert_eq!(Color::GRAY, image.get_pixel(4, 2));
+        assert_eq!(Color::BLACK, image.get_pixel(1, 1));
+        assert_eq!(Color::WHITE, image.get_pixel(5, 1));
+    }
+
+    #[test]
+    pub fn region_clips_cells() {
+        let mut board = DynamicVectorLifeBoard::empty();
+        board.draw_pattern(&Pattern::BLOCK(), &LifePoint::new(0, 0));
+        let region = (LifePoint::new(-10, -10), LifePoint::new(-9, -9));
+        let image = render(&board, Some(region), &options(1, false, 0)).unwrap();
+        assert!(image.to_rgb_bytes().iter().all(|b| *b == 255));
+    }
+
+    #[test]
+    pub fn custom_palette_is_used() {
+        let mut board = DynamicVectorLifeBoard::empty();
+        board.set_live(0, 0);
+        let red = Color::new(255, 0, 0);
+        let mut render_options = options(1, false, 0);
+        render_options.palette.live = red;
+        assert_eq!(
+            red,
+            render(&board, None, &render_options)
+                .unwrap()
+                .get_pixel(0, 0)
+        );
+    }
+
+    #[test]
+    pub fn png_is_written() {
+        let mut board = DynamicVectorLifeBoard::empty();
+        board.draw_pattern(&Pattern::GLIDER_SOUTHEAST(), &LifePoint::new(0, 0));
+        let png = render(&board, None, &RenderOptions::default())
+            .unwrap()
+            .to_png()
+            .unwrap();
+        assert_eq!(b"\x89PNG\r\n\x1a\n", &png[..8]);
+
+        let decoder = png::Decoder::new(png.as_slice());
+        let reader = decoder.read_info().unwrap();
+        assert_eq!((20, 20), (reader.info().width, reader.info().height));
+    }
+
+    #[test]
+    pub fn empty_images_are_rejected() {
+        let board = DynamicVectorLifeBoard::empty();
+        let origin = (LifePoint::new(0, 0), LifePoint::new(0, 0));
+        let inverted = (LifePoint::new(1, 1), LifePoint::new(0, 0));
+        assert_eq!(
+            Err(EngineError::InvalidImageSize {
+                width: 0,
+                height: 0
+            }),
+            render(&board, Some(origin), &options(0, false, 0))
+        );
+        assert!(render(&board, Some(inverted), &options(4, false, 0)).is_err());
+
+        //Grid lines alone are still something to draw
+        let image = render(&board, Some(origin), &options(0, true, 0)).unwrap();
+        assert_eq!((2, 2), (image.get_width(), image.get_height()));
+    }
+
+    #[test]
+    pub fn huge_images_are_rejected() {
+        let board = DynamicVectorLifeBoard::empty();
+        let everything = (
+            LifePoint::new(i64::MIN, i64::MIN),
+            LifePoint::new(i64::MAX, i64::MAX),
+        );
+        assert_eq!(
+            Err(EngineError::InvalidImageSize {
+                width: u64::MAX,
+                height: u64::MAX
+            }),
+            render(&board, Some(everything), &options(u32::MAX, true, 0))
+        );
+
+        let wide = (LifePoint::new(0, 0), LifePoint::new(1 << 20, 0));
+        assert!(image_size(wide, &options(1, false, 0)).is_ok());
+        assert!(image_size(wide, &options(1 << 10, false, 0)).is_err());
+    }
+}
@@ -0,0 +1,313 @@
+use crate::life_board::LifeBoard;
+use crate::life_point::LifePoint;
+use crate::pattern::Pattern;
+use std::cmp::Ordering;
+
+/// An immutable copy of the live cells on a board, independent of the `LifeBoard` implementation it was taken from.
+/// Two snapshots are equal if they contain exactly the same live cells, so this can be used to compare boards of different types.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(from = "Vec<LifePoint>", into = "Vec<LifePoint>")
+)]
+pub struct BoardSnapshot {
+    /// Always sorted and free of duplicates, so that equality and hashing don't depend on iteration order
+    cells: Vec<LifePoint>,
+}
+
+impl BoardSnapshot {
+    pub fn of(board: &dyn LifeBoard) -> BoardSnapshot {
+        BoardSnapshot::of_cells(board.get_live_cells())
+    }
+
+    pub fn of_cells(mut cells: Vec<LifePoint>) -> BoardSnapshot {
+        cells.sort_unstable();
+        cells.dedup();
+        BoardSnapshot { cells }
+    }
+
+    pub fn of_pattern(pattern: &Pattern) -> BoardSnapshot {
+        BoardSnapshot::of_cells(pattern.get_points().clone())
+    }
+
+    pub fn to_pattern(&self) -> Pattern {
+        Pattern::new(self.cells.clone())
+    }
+
+    pub fn get_cells(&self) -> &Vec<LifePoint> {
+        &self.cells
+    }
+
+    pub fn population(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Get the smallest and largest corners of the box containing all the live cells, or `None` if there aren't any
+    pub fn get_bounds(&self) -> Option<(LifePoint, LifePoint)> {
+        let first = self.cells.first()?;
+        let (mut x_min, mut y_min) = (first.get_x(), first.get_y());
+        let (mut x_max, mut y_max) = (x_min, y_min);
+        for cell in &self.cells {
+            x_min = x_min.min(cell.get_x());
+            y_min = y_min.min(cell.get_y());
+            x_max = x_max.max(cell.get_x());
+            y_max = y_max.max(cell.get_y());
+        }
+        Some((LifePoint::new(x_min, y_min), LifePoint::new(x_max, y_max)))
+    }
+
+    /// Get the cells that are live here but not in `previous` (births), and the ones live in `previous` but not here (deaths),
+    /// both in sorted order. Both cell lists are already sorted, so this is one walk along them together.
+    pub fn changes_since(&self, previous: &BoardSnapshot) -> (Vec<LifePoint>, Vec<LifePoint>) {
+        let (before, after) = (&previous.cells, &self.cells);
+        let (mut i, mut j) = (0, 0);
+        let (mut births, mut deaths) = (vec![], vec![]);
+        while i < before.len() && j < after.len() {
+            match before[i].cmp(&after[j]) {
+                Ordering::Less => {
+                    deaths.push(before[i]);
+                    i += 1;
+                }
+                Ordering::Greater => {
+                    births.push(after[j]);
+                    j += 1;
+                }
+                Ordering::Equal => {
+                    i += 1;
+                    j += 1;
+                }
+            }
+        }
+        deaths.extend_from_slice(&before[i..]);
+        births.extend_from_slice(&after[j..]);
+        (births, deaths)
+    }
+
+    /// Move the snapshot so that the top-left corner of its bounding box is at the origin
+    pub fn normalized(&self) -> BoardSnapshot {
+        match self.get_bounds() {
+            None => self.clone(),
+            Some((min, _)) => self.translated(-min.get_x(), -min.get_y()),
+        }
+    }
+
+    pub fn translated(&self, dx: i64, dy: i64) -> BoardSnapshot {
+        //Translation doesn't change the sort order, so there's no need to re-sort
+        BoardSnapshot {
+            cells: self.cells.iter().map(|p| p.offset(dx, dy)).collect(),
+        }
+    }
+
+    /// Get the snapshot in all eight rotations and reflections, each moved to the origin.
+    /// The first one is just the normalized snapshot, and the fifth swaps the x and y axes.
+    pub fn orientations(&self) -> Vec<BoardSnapshot> {
+        type Transform = fn(i64, i64) -> (i64, i64);
+        let transforms: [Transform; 8] = [
+            |x, y| (x, y),
+            |x, y| (-x, y),
+            |x, y| (x, -y),
+            |x, y| (-x, -y),
+            |x, y| (y, x),
+            |x, y| (-y, x),
+            |x, y| (y, -x),
+            |x, y| (-y, -x),
+        ];
+
+        transforms
+            .iter()
+            .map(|transform| {
+                let cells = self
+                    .cells
+                    .iter()
+                    .map(|p| {
+                        let (x, y) = transform(p.get_x(), p.get_y());
+                        LifePoint::new(x, y)
+                    })
+                    .collect();
+                BoardSnapshot::of_cells(cells).normalized()
+            })
+            .collect()
+    }
+
+    /// Check if the two snapshots have the same live cells after moving them both to the origin
+    pub fn same_shape(&self, other: &BoardSnapshot) -> bool {
+        self.population() == other.population() && self.normalized() == other.normalized()
+    }
+
+    /// A hash of the live cells that doesn't change when the pattern is moved around the board.
+    /// This is FNV-1a over the normalized coordinates, so it gives the same value on every machine and every run,
+    /// and can be stored alongside results. As with any hash, equal hashes don't guarantee equal shapes.
+    pub fn content_hash(&self) -> u64 {
+        const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+        const FNV_PRIME: u64 = 0x100000001b3;
+
+        let mut hash = FNV_OFFSET_BASIS;
+        for cell in self.normalized().cells {
+            for byte in cell
+                .get_x()
+                .to_le_bytes()
+                .iter()
+                .chain(cell.get_y().to_le_bytes().iter())
+            {
+                hash ^= *byte as u64;
+                hash = hash.wrapping_mul(FNV_PRIME);
+            }
+        }
+        hash
+    }
+}
+
+impl From<Vec<LifePoint>> for BoardSnapshot {
+    fn from(cells: Vec<LifePoint>) -> BoardSnapshot {
+        BoardSnapshot::of_cells(cells)
+    }
+}
+
+impl From<BoardSnapshot> for Vec<LifePoint> {
+    fn from(snapshot: BoardSnapshot) -> Vec<LifePoint> {
+        snapshot.cells
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{DynamicVectorLifeBoard, FixedBitfieldLifeBoard, FixedVectorLifeBoard};
+
+    #[test]
+    pub fn snapshots_match_across_implementations() {
+        let mut vector_board = DynamicVectorLifeBoard::empty();
+        let mut bitfield_board = FixedBitfieldLifeBoard::empty();
+        vector_board.draw_pattern(&Pattern::BEACON(), &LifePoint::new(10, 10));
+        bitfield_board.draw_pattern(&Pattern::BEACON(), &LifePoint::new(10, 10));
+
+        for _ in 0..3 {
+            assert_eq!(
+                BoardSnapshot::of(&vector_board),
+                BoardSnapshot::of(&bitfield_board)
+            );
+            assert!(vector_board.has_same_cells(&bitfield_board));
+            vector_board.step_one();
+            bitfield_board.step_one();
+        }
+    }
+
+    #[test]
+    pub fn has_same_cells_detects_differences() {
+        let mut vector_board = FixedVectorLifeBoard::empty();
+        let mut bitfield_board = FixedBitfieldLifeBoard::empty();
+        vector_board.set_live(3, 3);
+        bitfield_board.set_live(3, 4);
+
+        assert!(!vector_board.has_same_cells(&bitfield_board));
+        assert!(!bitfield_board.has_same_cells(&vector_board));
+    }
+
+    #[test]
+    pub fn snapshot_ignores_order_and_duplicates() {
+        let a = BoardSnapshot::of_cells(vec![LifePoint::new(1, 2), LifePoint::new(0, 0)]);
+        let b = BoardSnapshot::of_cells(vec![
+            LifePoint::new(0, 0),
+            LifePoint::new(1, 2),
+            LifePoint::new(0, 0),
+        ]);
+        assert_eq!(a, b);
+        assert_eq!(2, b.population());
+    }
+
+    #[test]
+    pub fn content_hash_is_translation_invariant() {
+        let mut board = DynamicVectorLifeBoard::empty();
+        board.draw_pattern(&Pattern::GLIDER_SOUTHEAST(), &LifePoint::new(0, 0));
+        let here = BoardSnapshot::of(&board);
+
+        let mut other_board = DynamicVectorLifeBoard::empty();
+        other_board.draw_pattern(&Pattern::GLIDER_SOUTHEAST(), &LifePoint::new(-40, 17));
+        let there = BoardSnapshot::of(&other_board);
+
+        assert_ne!(here, there);
+        assert!(here.same_shape(&there));
+        assert_eq!(here.content_hash(), there.content_hash());
+    }
+
+    #[test]
+    pub fn content_hash_differs_for_different_shapes() {
+        let block = BoardSnapshot::of_pattern(&Pattern::BLOCK());
+        let beacon = BoardSnapshot::of_pattern(&Pattern::BEACON());
+        assert!(!block.same_shape(&beacon));
+        assert_ne!(block.content_hash(), beacon.content_hash());
+    }
+
+    #[test]
+    pub fn changes_since_works() {
+        let before = BoardSnapshot::of_pattern(&Pattern::BLINKER());
+        let after = BoardSnapshot::of_cells(vec![
+            LifePoint::new(0, -1),
+            LifePoint::new(0, 0),
+            LifePoint::new(0, 1),
+        ]);
+        let (births, deaths) = after.changes_since(&before);
+        assert_eq!(vec![LifePoint::new(0, -1), LifePoint::new(0, 1)], births);
+        assert_eq!(vec![LifePoint::new(-1, 0), LifePoint::new(1, 0)], deaths);
+
+        let empty = BoardSnapshot::of_cells(vec![]);
+        assert_eq!(
+            (before.get_cells().clone(), vec![]),
+            before.changes_since(&empty)
+        );
+        assert_eq!(
+            (vec![], before.get_cells().clone()),
+            empty.changes_since(&before)
+        );
+        assert_eq!((vec![], vec![]), before.changes_since(&before));
+    }
+
+    #[test]
+    pub fn get_bounds_works() {
+        let empty = BoardSnapshot::of_cells(vec![]);
+        assert_eq!(None, empty.get_bounds());
+
+        let snapshot = BoardSnapshot::of_pattern(&Pattern::ACORN());
+        assert_eq!(
+            Some((LifePoint::new(-3, -1), LifePoint::new(3, 1))),
+            snapshot.get_bounds()
+        );
+        assert_eq!(
+            Some((LifePoint::new(0, 0), LifePoint::new(6, 2))),
+            snapshot.normalized().get_bounds()
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    pub fn serde_round_trips() {
+        let pattern = Pattern::GLIDER_SOUTHEAST();
+        let json = serde_json::to_string(&pattern).unwrap();
+        assert_eq!(pattern, serde_json::from_str(&json).unwrap());
+
+        let point = LifePoint::new(-3, 7);
+        assert_eq!("{\"x\":-3,\"y\":7}", serde_json::to_string(&point).unwrap());
+
+        let snapshot = BoardSnapshot::of_pattern(&Pattern::BEACON());
+        let json = serde_json::to_string(&snapshot).unwrap();
+        assert!(json.starts_with("[{"));
+        assert_eq!(snapshot, serde_json::from_str(&json).unwrap());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    pub fn deserialized_snapshots_are_sorted() {
+        let json = "[{\"x\":5,\"y\":5},{\"x\":0,\"y\":0},{\"x\":5,\"y\":5}]";
+        let snapshot: BoardSnapshot = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            BoardSnapshot::of_cells(vec![LifePoint::new(0, 0), LifePoint::new(5, 5)]),
+            snapshot
+        );
+        assert_eq!(2, snapshot.population());
+    }
+}
@@ -0,0 +1,279 @@
+//! Random starting patterns ("soups") that can be reproduced exactly from a seed string.
+//!
+//! The generator is deliberately simple so that it gives the same results on every machine and every version of Rust:
+//! the seed string is hashed with 64-bit FNV-1a, and that hash seeds a SplitMix64 generator.
+//! Cells are filled in row by row, top to bottom and left to right, using one random number per cell
+//! (or per group of cells that symmetry forces to be the same). Changing any of this changes every soup, so don't.
+
+use crate::error::EngineError;
+use crate::life_point::LifePoint;
+use crate::pattern::Pattern;
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+/// A SplitMix64 pseudo-random number generator. Not suitable for anything security-related.
+pub struct SoupRng {
+    state: u64,
+}
+
+impl SoupRng {
+    pub fn from_seed(seed: &str) -> SoupRng {
+        const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+        const FNV_PRIME: u64 = 0x100000001b3;
+
+        let mut hash = FNV_OFFSET_BASIS;
+        for byte in seed.as_bytes() {
+            hash ^= *byte as u64;
+            hash = hash.wrapping_mul(FNV_PRIME);
+        }
+        SoupRng { state: hash }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in the range `[0, 1)`, using the top 53 bits of the next value
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// The symmetry forced onto a soup, named after its symmetry group.
+/// apgsearch also names its symmetries after where the center or mirror lines fall, which here depends on the soup size:
+/// on a cell for odd sizes, and between cells for even ones. Each variant lists the apgsearch symmetry it matches for each.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Symmetry {
+    /// No symmetry (apgsearch `C1`)
+    C1,
+    /// Unchanged by a half turn (apgsearch `C2_1` for odd sizes, `C2_4` for even ones)
+    C2,
+    /// Unchanged by a quarter turn (apgsearch `C4_1` for odd sizes, `C4_4` for even ones)
+    C4,
+    /// Mirrored left-to-right (apgsearch `D2_+1` for odd sizes, `D2_+2` for even ones)
+    D2,
+    /// Mirrored left-to-right and top-to-bottom (apgsearch `D4_+1` for odd sizes, `D4_+4` for even ones)
+    D4,
+    /// Unchanged by any rotation or reflection of the square (apgsearch `D8_1` for odd sizes, `D8_4` for even ones)
+    D8,
+}
+
+impl Symmetry {
+    /// Get every cell that has to match the given cell, including itself, in a square of the given size
+    fn images(&self, x: i64, y: i64, size: i64) -> Vec<(i64, i64)> {
+        let max = size - 1;
+        match self {
+            Symmetry::C1 => vec![(x, y)],
+            Symmetry::C2 => vec![(x, y), (max - x, max - y)],
+            Symmetry::C4 => vec![(x, y), (max - y, x), (max - x, max - y), (y, max - x)],
+            Symmetry::D2 => vec![(x, y), (max - x, y)],
+            Symmetry::D4 => vec![(x, y), (max - x, y), (x, max - y), (max - x, max - y)],
+            Symmetry::D8 => vec![
+                (x, y),
+                (max - x, y),
+                (x, max - y),
+                (max - x, max - y),
+                (y, x),
+                (max - y, x),
+                (y, max - x),
+                (max - y, max - x),
+            ],
+        }
+    }
+}
+
+impl fmt::Display for Symmetry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl FromStr for Symmetry {
+    type Err = EngineError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_uppercase().as_str() {
+            "C1" => Ok(Symmetry::C1),
+            "C2" => Ok(Symmetry::C2),
+            "C4" => Ok(Symmetry::C4),
+            "D2" => Ok(Symmetry::D2),
+            "D4" => Ok(Symmetry::D4),
+            "D8" => Ok(Symmetry::D8),
+            _ => Err(EngineError::UnknownSymmetry(s.to_owned())),
+        }
+    }
+}
+
+/// Generate a `size` by `size` soup where each cell is live with probability `density`.
+/// The soup is centered on the origin, so it can be drawn at any point with `LifeBoard::draw_pattern`.
+pub fn generate_soup(seed: &str, size: u32, density: f64, symmetry: Symmetry) -> Pattern {
+    let mut rng = SoupRng::from_seed(seed);
+    let size = size as i64;
+    let offset = size / 2;
+
+    //Cells that have to match each other all take their value from whichever of them is filled in first
+    let mut filled: HashMap<(i64, i64), bool> = HashMap::new();
+    let mut points = vec![];
+    for y in 0..size {
+        for x in 0..size {
+            let first = symmetry
+                .images(x, y, size)
+                .into_iter()
+                .min_by_key(|(ix, iy)| (*iy, *ix))
+                .unwrap();
+            let live = match filled.get(&first) {
+                Some(live) => *live,
+                None => rng.next_f64() < density,
+            };
+            filled.insert((x, y), live);
+
+            if live {
+                points.push(LifePoint::new(x - offset, y - offset));
+            }
+        }
+    }
+
+    Pattern::new(points)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::snapshot::BoardSnapshot;
+    use crate::{FixedBitfieldLifeBoard, LifeBoard};
+    use std::collections::HashSet;
+
+    #[test]
+    pub fn rng_is_stable() {
+        //If this changes, every soup ever generated changes with it
+        let mut rng = SoupRng::from_seed("conway");
+        let values: Vec<u64> = (0..3).map(|_| rng.next_u64()).collect();
+        let mut again = SoupRng::from_seed("conway");
+        assert_eq!(
+            values,
+            (0..3).map(|_| again.next_u64()).collect::<Vec<u64>>()
+        );
+        assert_eq!(
+            vec![0x53cda1a3d41894c5, 0xf8f52d9548eb33d5, 0x8906ba69d6a819b5],
+            values
+        );
+    }
+
+    #[test]
+    pub fn same_seed_gives_same_soup() {
+        let a = generate_soup("k_abc123", 16, 0.5, Symmetry::C1);
+        let b = generate_soup("k_abc123", 16, 0.5, Symmetry::C1);
+        let c = generate_soup("k_abc124", 16, 0.5, Symmetry::C1);
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    pub fn density_is_respected() {
+        let soup = generate_soup("density", 100, 0.25, Symmetry::C1);
+        let count = soup.get_points().len();
+        assert!((2200..2800).contains(&count), "{} live cells", count);
+
+        assert!(generate_soup("empty", 16, 0.0, Symmetry::C1)
+            .get_points()
+            .is_empty());
+        assert_eq!(
+            256,
+            generate_soup("full", 16, 1.0, Symmetry::D8)
+                .get_points()
+                .len()
+        );
+    }
+
+    #[test]
+    pub fn soup_is_centered() {
+        let soup = BoardSnapshot::of_pattern(&generate_soup("centered", 16, 1.0, Symmetry::C1));
+        assert_eq!(
+            Some((LifePoint::new(-8, -8), LifePoint::new(7, 7))),
+            soup.get_bounds()
+        );
+    }
+
+    #[test]
+    pub fn symmetry_is_respected() {
+        const SIZE: i64 = 16;
+        const MAX: i64 = SIZE - 1;
+        //Each transformation of the square, and the symmetries that have to leave a soup unchanged by it
+        type Transform = fn(i64, i64) -> (i64, i64);
+        let transforms: [(&str, Transform, &[Symmetry]); 5] = [
+            (
+                "half turn",
+                |x, y| (MAX - x, MAX - y),
+                &[Symmetry::C2, Symmetry::C4, Symmetry::D4, Symmetry::D8],
+            ),
+            (
+                "quarter turn",
+                |x, y| (MAX - y, x),
+                &[Symmetry::C4, Symmetry::D8],
+            ),
+            (
+                "left-right mirror",
+                |x, y| (MAX - x, y),
+                &[Symmetry::D2, Symmetry::D4, Symmetry::D8],
+            ),
+            (
+                "top-bottom mirror",
+                |x, y| (x, MAX - y),
+                &[Symmetry::D4, Symmetry::D8],
+            ),
+            ("diagonal mirror", |x, y| (y, x), &[Symmetry::D8]),
+        ];
+
+        for symmetry in [
+            Symmetry::C1,
+            Symmetry::C2,
+            Symmetry::C4,
+            Symmetry::D2,
+            Symmetry::D4,
+            Symmetry::D8,
+        ] {
+            //Cells in the soup's own square, from (0, 0) to (MAX, MAX)
+            let cells: HashSet<(i64, i64)> = generate_soup("sym", SIZE as u32, 1.0 / 3.0, symmetry)
+                .get_points()
+                .iter()
+                .map(|p| (p.get_x() + SIZE / 2, p.get_y() + SIZE / 2))
+                .collect();
+            for (name, transform, invariant_under) in transforms.iter() {
+                let transformed: HashSet<(i64, i64)> =
+                    cells.iter().map(|(x, y)| transform(*x, *y)).collect();
+                assert_eq!(
+                    invariant_under.contains(&symmetry),
+                    transformed == cells,
+                    "{} soup and {}",
+                    symmetry,
+                    name
+                );
+            }
+        }
+    }
+
+    #[test]
+    pub fn soup_can_be_drawn() {
+        let soup = generate_soup("draw", 16, 0.5, Symmetry::C2);
+        let mut board = FixedBitfieldLifeBoard::empty();
+        board.draw_pattern(&soup, &LifePoint::new(100, 100));
+        assert_eq!(soup.get_points().len(), board.get_live_cells().len());
+    }
+
+    #[test]
+    pub fn symmetry_parses() {
+        assert_eq!(Ok(Symmetry::D8), "d8".parse());
+        assert_eq!(Ok(Symmetry::C1), "C1".parse());
+        assert_eq!(
+            Err(EngineError::UnknownSymmetry("C3".to_owned())),
+            "C3".parse::<Symmetry>()
+        );
+        assert_eq!("C4", Symmetry::C4.to_string());
+    }
+}
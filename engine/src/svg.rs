@@ -0,0 +1,311 @@
+//! SVG drawings of a board or pattern, which scale cleanly in papers and slides.
+
+use crate::error::{check_image_size, EngineError};
+use crate::life_board::LifeBoard;
+use crate::life_point::LifePoint;
+use crate::pattern::Pattern;
+use crate::render::{resolve_region, Color, Palette};
+use crate::snapshot::BoardSnapshot;
+use std::fmt::Write;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SvgOptions {
+    /// Width and height of each cell in SVG user units
+    pub cell_size: u32,
+    /// Draw lines between the cells
+    pub grid_lines: bool,
+    /// Draw the x and y axes through the origin, if they're in the region
+    pub axes: bool,
+    /// Draw each horizontal run of live cells as one rectangle, which makes much smaller files
+    pub merge_runs: bool,
+    pub palette: Palette,
+    pub axis_color: Color,
+    /// Cells to draw a highlight over, whether they're live or not
+    pub highlighted: Vec<LifePoint>,
+    pub highlight_color: Color,
+    /// Number of blank cells to leave around the live cells when no region is given
+    pub margin: i64,
+}
+
+impl Default for SvgOptions {
+    fn default() -> SvgOptions {
+        SvgOptions {
+            cell_size: 10,
+            grid_lines: false,
+            axes: false,
+            merge_runs: true,
+            palette: Palette::default(),
+            axis_color: Color::new(0, 0, 255),
+            highlighted: vec![],
+            highlight_color: Color::new(255, 0, 0),
+            margin: 1,
+        }
+    }
+}
+
+/// Draw the region of the board given by its top-left and bottom-right corners (inclusive),
+/// or the live cells plus the margin in `options` if no region is given.
+/// Fails if the drawing would have no area, or more than `MAX_IMAGE_PIXELS` square units.
+pub fn board_to_svg(
+    board: &dyn LifeBoard,
+    region: Option<(LifePoint, LifePoint)>,
+    options: &SvgOptions,
+) -> Result<String, EngineError> {
+    snapshot_to_svg(&BoardSnapshot::of(board), region, options)
+}
+
+/// Draw the pattern's cells plus the margin in `options`, with the pattern's origin as the board origin
+pub fn pattern_to_svg(pattern: &Pattern, options: &SvgOptions) -> Result<String, EngineError> {
+    snapshot_to_svg(&BoardSnapshot::of_pattern(pattern), None, options)
+}
+
+/// Draw a snapshot in the same way as `board_to_svg`
+pub fn snapshot_to_svg(
+    snapshot: &BoardSnapshot,
+    region: Option<(LifePoint, LifePoint)>,
+    options: &SvgOptions,
+) -> Result<String, EngineError> {
+    let (min, max) = resolve_region(snapshot, region, options.margin);
+    //Worked out in i128 and checked first, after which everything fits easily in an i64
+    let side = |first: i64, last: i64| {
+        (last as i128 - first as i128 + 1).max(0) * options.cell_size as i128
+    };
+    let (width, height) = check_image_size(
+        side(min.get_x(), max.get_x()),
+        side(min.get_y(), max.get_y()),
+    )?;
+    let size = options.cell_size as i64;
+    let (width, height) = (width as i64, height as i64);
+    let (columns, rows) = (width / size, height / size);
+    //Board coordinates to SVG coordinates, with the region's top-left corner at 0,0
+    let to_x = |x: i64| (x - min.get_x()) * size;
+    let to_y = |y: i64| (y - min.get_y()) * size;
+    let is_inside = |cell: &LifePoint| {
+        (min.get_x()..=max.get_x()).contains(&cell.get_x())
+            && (min.get_y()..=max.get_y()).contains(&cell.get_y())
+    };
+
+    //Writing to a String can't fail, so the results of write! are ignored throughout
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{1}\" viewBox=\"0 0 {0} {1}\">",
+        width, height
+    );
+    let _ = writeln!(
+        svg,
+        "<rect width=\"{}\" height=\"{}\" fill=\"{}\"/>",
+        width,
+        height,
+        hex(options.palette.dead)
+    );
+
+    let _ = writeln!(svg, "<g fill=\"{}\">", hex(options.palette.live));
+    let live: Vec<&LifePoint> = snapshot
+        .get_cells()
+        .iter()
+        .filter(|c| is_inside(c))
+        .collect();
+    for (start, length) in cell_runs(&live, options.merge_runs) {
+        let _ = writeln!(
+            svg,
+            "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"/>",
+            to_x(start.get_x()),
+            to_y(start.get_y()),
+            length * size,
+            size
+        );
+    }
+    svg.push_str("</g>\n");
+
+    if options.grid_lines {
+        let _ = writeln!(
+            svg,
+            "<g stroke=\"{}\" stroke-width=\"1\">",
+            hex(options.palette.grid)
+        );
+        for column in 0..=columns {
+            let x = column * size;
+            let _ = writeln!(
+                svg,
+                "<line x1=\"{0}\" y1=\"0\" x2=\"{0}\" y2=\"{1}\"/>",
+                x, height
+            );
+        }
+        for row in 0..=rows {
+            let y = row * size;
+            let _ = writeln!(
+                svg,
+                "<line x1=\"0\" y1=\"{0}\" x2=\"{1}\" y2=\"{0}\"/>",
+                y, width
+            );
+        }
+        svg.push_str("</g>\n");
+    }
+
+    if options.axes {
+        let _ = writeln!(
+            svg,
+            "<g stroke=\"{}\" stroke-width=\"2\">",
+            hex(options.axis_color)
+        );
+        //Each axis runs through the middle of the row or column of cells at 0
+        if (min.get_x()..=max.get_x()).contains(&0) {
+            let x = to_x(0) + size / 2;
+            let _ = writeln!(
+                svg,
+                "<line x1=\"{0}\" y1=\"0\" x2=\"{0}\" y2=\"{1}\"/>",
+                x, height
+            );
+        }
+        if (min.get_y()..=max.get_y()).contains(&0) {
+            let y = to_y(0) + size / 2;
+            let _ = writeln!(
+                svg,
+                "<line x1=\"0\" y1=\"{0}\" x2=\"{1}\" y2=\"{0}\"/>",
+                y, width
+            );
+        }
+        svg.push_str("</g>\n");
+    }
+
+    if !options.highlighted.is_empty() {
+        let _ = writeln!(
+            svg,
+            "<g fill=\"{}\" fill-opacity=\"0.5\">",
+            hex(options.highlight_color)
+        );
+        for cell in options.highlighted.iter().filter(|c| is_inside(c)) {
+            let _ = writeln!(
+                svg,
+                "<rect x=\"{}\" y=\"{}\" width=\"{2}\" height=\"{2}\"/>",
+                to_x(cell.get_x()),
+                to_y(cell.get_y()),
+                size
+            );
+        }
+        svg.push_str("</g>\n");
+    }
+
+    svg.push_str("</svg>\n");
+    Ok(svg)
+}
+
+/// Group sorted cells into horizontal runs, giving the leftmost cell and length of each.
+/// Without merging, every cell is a run of its own.
+fn cell_runs(cells: &[&LifePoint], merge_runs: bool) -> Vec<(LifePoint, i64)> {
+    //Cells are sorted by x then y, so re-sort by row to find the runs
+    let mut cells: Vec<LifePoint> = cells.iter().map(|c| **c).collect();
+    cells.sort_by_key(|c| (c.get_y(), c.get_x()));
+
+    let mut runs: Vec<(LifePoint, i64)> = vec![];
+    for cell in cells {
+        match runs.last_mut() {
+            Some((start, length))
+                if merge_runs
+                    && start.get_y() == cell.get_y()
+                    && start.get_x() + *length == cell.get_x() =>
+            {
+                *length += 1
+            }
+            _ => runs.push((cell, 1)),
+        }
+    }
+    runs
+}
+
+fn hex(color: Color) -> String {
+    format!("#{:02x}{:02x}{:02x}", color.r, color.g, color.b)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::DynamicVectorLifeBoard;
+
+    fn count(svg: &str, needle: &str) -> usize {
+        svg.matches(needle).count()
+    }
+
+    #[test]
+    pub fn cells_are_drawn() {
+        let options = SvgOptions {
+            merge_runs: false,
+            ..SvgOptions::default()
+        };
+        let svg = pattern_to_svg(&Pattern::GLIDER_SOUTHEAST(), &options).unwrap();
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.contains("width=\"50\" height=\"50\""));
+        //One background rect plus one per cell
+        assert_eq!(6, count(&svg, "<rect"));
+    }
+
+    #[test]
+    pub fn runs_are_merged() {
+        let svg = pattern_to_svg(&Pattern::BLINKER(), &SvgOptions::default()).unwrap();
+        assert_eq!(2, count(&svg, "<rect"));
+        assert!(svg.contains("<rect x=\"10\" y=\"10\" width=\"30\" height=\"10\"/>"));
+    }
+
+    #[test]
+    pub fn grid_and_axes_are_optional() {
+        let plain = pattern_to_svg(&Pattern::BLOCK(), &SvgOptions::default()).unwrap();
+        assert_eq!(0, count(&plain, "<line"));
+
+        let options = SvgOptions {
+            grid_lines: true,
+            axes: true,
+            margin: 0,
+            ..SvgOptions::default()
+        };
+        let svg = pattern_to_svg(&Pattern::BLOCK(), &options).unwrap();
+        //Three lines each way for a 2x2 grid, plus both axes
+        assert_eq!(8, count(&svg, "<line"));
+    }
+
+    #[test]
+    pub fn highlights_are_drawn_in_the_region() {
+        let mut board = DynamicVectorLifeBoard::empty();
+        board.set_live(0, 0);
+        let options = SvgOptions {
+            highlighted: vec![LifePoint::new(1, 1), LifePoint::new(50, 50)],
+            ..SvgOptions::default()
+        };
+        let region = (LifePoint::new(0, 0), LifePoint::new(2, 2));
+        let svg = board_to_svg(&board, Some(region), &options).unwrap();
+        assert_eq!(1, count(&svg, "fill-opacity"));
+        assert!(svg.contains("<rect x=\"10\" y=\"10\" width=\"10\" height=\"10\"/>\n</g>"));
+        assert_eq!(3, count(&svg, "<rect"));
+    }
+
+    #[test]
+    pub fn oversized_drawings_are_rejected() {
+        let far = LifePoint::new(i64::MAX - 1, 0);
+        let board = DynamicVectorLifeBoard::empty();
+        assert!(board_to_svg(
+            &board,
+            Some((LifePoint::new(0, 0), far)),
+            &SvgOptions::default()
+        )
+        .is_err());
+
+        let huge_cells = SvgOptions {
+            cell_size: u32::MAX,
+            ..SvgOptions::default()
+        };
+        assert!(pattern_to_svg(&Pattern::BLOCK(), &huge_cells).is_err());
+        let empty = (LifePoint::new(1, 0), LifePoint::new(0, 0));
+        assert_eq!(
+            Err(EngineError::InvalidImageSize {
+                width: 0,
+                height: 10
+            }),
+            board_to_svg(&board, Some(empty), &SvgOptions::default())
+        );
+    }
+
+    #[test]
+    pub fn colors_are_hex() {
+        assert_eq!("#ff0080", hex(Color::new(255, 0, 128)));
+    }
+}
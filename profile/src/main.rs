@@ -1,22 +1,50 @@
 #![allow(unused_imports)]
-use engine::life_board::{BoardPoint, LifeBoard};
+use engine::analysis::measure_pattern_lifespan;
+use engine::generations::steps;
+use engine::life_board::LifeBoard;
+use engine::life_point::LifePoint;
 use engine::pattern::Pattern;
+use engine::registry::{board_names, find_board};
+use engine::render::{save_png, RenderOptions};
 use std::io;
+use std::path::PathBuf;
 use std::time::{Duration, Instant};
 
 fn main() {
-    // let mut life_board = engine::new_fixed_vector_board();
-    // let mut life_board = engine::new_dynamic_vector_board();
-    // let mut life_board = engine::new_dynamic_array2d_board();
-    let mut life_board = engine::new_fixed_bitfield_board();
-    life_board.draw_pattern(&Pattern::ACORN(), &BoardPoint::new(128, 128));
+    //`--board NAME` picks the implementation to profile, and `--png PATH` saves a picture of the board once the test is done
+    let mut board_name = "fixed-bitfield".to_owned();
+    let mut png_path = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match (arg.as_str(), args.next()) {
+            ("--board", Some(name)) => board_name = name,
+            ("--png", Some(path)) => png_path = Some(PathBuf::from(path)),
+            _ => usage_error(),
+        }
+    }
+    let board_info = match find_board(&board_name) {
+        Ok(info) => info,
+        Err(e) => {
+            eprintln!("{}", e);
+            usage_error();
+        }
+    };
+
+    //Run for exactly as long as the acorn takes to settle, rather than a guess
+    let lifespan = measure_pattern_lifespan(&Pattern::ACORN(), 10000);
+    let generations = lifespan.stabilization_generation.unwrap();
+    println!(
+        "Acorn settles after {} generations, with a population of {} including {} gliders",
+        generations, lifespan.final_population, lifespan.gliders
+    );
+
+    let mut life_board = board_info.create();
+    life_board.draw_pattern(&Pattern::ACORN(), &board_info.center);
 
     println!("Beginning test...");
     let start = Instant::now();
 
-    for _i in 0..5000 {
-        life_board.step_one();
-    }
+    steps(life_board.as_mut()).nth(generations as usize);
 
     let end = Instant::now();
     let elapsed = end.duration_since(start);
@@ -30,4 +58,19 @@ fn main() {
         .collect::<Vec<String>>()
         .join("\n");
     println!("Board stats:\n{}", stats_text);
+
+    if let Some(path) = png_path {
+        match save_png(life_board.as_ref(), None, &RenderOptions::default(), &path) {
+            Ok(_) => println!("Board saved to {}", path.display()),
+            Err(e) => eprintln!("Couldn't save {}: {}", path.display(), e),
+        }
+    }
+}
+
+fn usage_error() -> ! {
+    eprintln!(
+        "Usage: profile [--board {}] [--png PATH]",
+        board_names().join("|")
+    );
+    std::process::exit(2);
 }
@@ -0,0 +1,410 @@
+use engine::analysis::measure_lifespan;
+use engine::census::Census;
+use engine::registry::{available_boards, find_board, BoardInfo};
+use engine::soup::{generate_soup, Symmetry};
+use engine::Rectangle;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Instant;
+
+const USAGE: &str = "Usage: search [options]
+    --soups N              total number of soups to search (default 1000)
+    --seed-prefix TEXT     soup seeds are this prefix followed by the soup number (default k_)
+    --size N               soups are N by N cells (default 16)
+    --density D            chance of each soup cell being live (default 0.5)
+    --symmetry S           one of C1, C2, C4, D2, D4, D8 (default C1)
+    --board NAME           board implementation, from the list below (default dynamic-array2d)
+    --max-generations N    give up on soups that haven't settled after N generations (default 20000)
+    --methuselah N         report soups that take at least N generations to settle (default 2000)
+    --threads N            worker threads (default: one per CPU core)
+    --output PATH          results file (default search_results.txt)
+    --checkpoint PATH      checkpoint file, resumed from if it exists (default search.checkpoint)";
+
+/// apgcode of the glider, which escaped gliders are counted under
+const GLIDER_APGCODE: &str = "xq4_153";
+/// How many soups each thread runs between checkpoints
+const SOUPS_PER_THREAD_PER_BATCH: u64 = 50;
+
+struct Settings {
+    soups: u64,
+    seed_prefix: String,
+    size: u32,
+    density: f64,
+    symmetry: Symmetry,
+    board: &'static BoardInfo,
+    max_generations: u64,
+    methuselah_generations: u64,
+    threads: u64,
+    output: PathBuf,
+    checkpoint: PathBuf,
+}
+
+impl Settings {
+    fn from_args(mut args: impl Iterator<Item = String>) -> Result<Settings, String> {
+        let mut settings = Settings {
+            soups: 1000,
+            seed_prefix: "k_".to_owned(),
+            size: 16,
+            density: 0.5,
+            symmetry: Symmetry::C1,
+            board: find_board("dynamic-array2d").map_err(|e| e.to_string())?,
+            max_generations: 20000,
+            methuselah_generations: 2000,
+            threads: thread::available_parallelism()
+                .map(|n| n.get() as u64)
+                .unwrap_or(1),
+            output: PathBuf::from("search_results.txt"),
+            checkpoint: PathBuf::from("search.checkpoint"),
+        };
+
+        while let Some(arg) = args.next() {
+            if arg == "--help" || arg == "-h" {
+                return Err(usage());
+            }
+
+            let value = args
+                .next()
+                .ok_or_else(|| format!("Missing value for {}", arg))?;
+            match arg.as_str() {
+                "--soups" => settings.soups = parse(&value, &arg)?,
+                "--seed-prefix" => settings.seed_prefix = value.clone(),
+                "--size" => settings.size = parse(&value, &arg)?,
+                "--density" => settings.density = parse(&value, &arg)?,
+                "--symmetry" => {
+                    settings.symmetry = value.parse::<Symmetry>().map_err(|e| e.to_string())?
+                }
+                "--board" => settings.board = find_board(&value).map_err(|e| e.to_string())?,
+                "--max-generations" => settings.max_generations = parse(&value, &arg)?,
+                "--methuselah" => settings.methuselah_generations = parse(&value, &arg)?,
+                "--threads" => settings.threads = parse(&value, &arg)?,
+                "--output" => settings.output = PathBuf::from(&value),
+                "--checkpoint" => settings.checkpoint = PathBuf::from(&value),
+                _ => return Err(format!("Unknown option {}\n{}", arg, usage())),
+            }
+        }
+
+        if settings.seed_prefix.chars().any(char::is_whitespace) {
+            return Err("The seed prefix can't contain whitespace".to_owned());
+        }
+        if settings.threads == 0 {
+            return Err("At least one thread is needed".to_owned());
+        }
+        if let Some(bounds) = settings.board.bounds {
+            let soup_area = settings.soup_area();
+            if bounds.intersection(&soup_area) != soup_area {
+                return Err(format!(
+                    "{}x{} soups don't fit on the {}x{} {} board",
+                    settings.size,
+                    settings.size,
+                    bounds.get_width(),
+                    bounds.get_height(),
+                    settings.board.name
+                ));
+            }
+        }
+        Ok(settings)
+    }
+
+    /// The cells each soup covers once it's drawn around the board's center
+    fn soup_area(&self) -> Rectangle {
+        let size = self.size as i64;
+        let center = self.board.center;
+        Rectangle::new(
+            center.get_x() - size / 2,
+            center.get_y() - size / 2,
+            size,
+            size,
+        )
+    }
+
+    /// A one-line summary of everything that affects the results, so a checkpoint can't be resumed with different settings
+    fn describe(&self) -> String {
+        format!(
+            "{} {} {} {} {} {} {}",
+            self.seed_prefix,
+            self.size,
+            self.density,
+            self.symmetry,
+            self.board.name,
+            self.max_generations,
+            self.methuselah_generations
+        )
+    }
+}
+
+fn parse<T: FromStr>(value: &str, arg: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("Invalid value '{}' for {}", value, arg))
+}
+
+/// The usage message, with every board implementation that can be picked
+fn usage() -> String {
+    let boards: Vec<String> = available_boards()
+        .iter()
+        .map(|info| format!("    {:<22} {}", info.name, info.description))
+        .collect();
+    format!("{}\n\nBoards:\n{}", USAGE, boards.join("\n"))
+}
+
+struct SoupResult {
+    seed: String,
+    /// Generation the soup settled at, or `None` if it didn't settle within the limit
+    stabilized_at: Option<u64>,
+    /// apgcode (or "unidentified") and count of each object, and whether it's one of the well-known objects
+    objects: Vec<(String, u64, bool)>,
+}
+
+fn run_soup(settings: &Settings, index: u64) -> SoupResult {
+    let seed = format!("{}{}", settings.seed_prefix, index);
+    let soup = generate_soup(&seed, settings.size, settings.density, settings.symmetry);
+    //The soup was checked to fit on the board when the settings were parsed
+    let mut board = settings.board.create();
+    board.draw_pattern(&soup, &settings.board.center);
+
+    let lifespan = measure_lifespan(board.as_mut(), settings.max_generations);
+    let mut objects: Vec<(String, u64, bool)> = Census::take(board.as_ref())
+        .get_entries()
+        .iter()
+        .map(|entry| {
+            let code = entry
+                .apgcode
+                .clone()
+                .unwrap_or_else(|| "unidentified".to_owned());
+            (code, entry.count, entry.name.is_some())
+        })
+        .collect();
+    //Gliders that escaped were taken off the board before the census, so they need adding back
+    if lifespan.gliders > 0 {
+        objects.push((GLIDER_APGCODE.to_owned(), lifespan.gliders, true));
+    }
+
+    SoupResult {
+        seed,
+        stabilized_at: lifespan.stabilization_generation,
+        objects,
+    }
+}
+
+/// Totals for every soup searched so far
+struct Results {
+    next_soup: u64,
+    counts: BTreeMap<String, u64>,
+    /// apgcode of each object not in the engine's pattern library, and the first soup it was seen in
+    rare: BTreeMap<String, String>,
+    /// Seed and settling generation of each long-lived soup, or `None` if it never settled
+    methuselahs: Vec<(String, Option<u64>)>,
+}
+
+impl Results {
+    fn empty() -> Results {
+        Results {
+            next_soup: 0,
+            counts: BTreeMap::new(),
+            rare: BTreeMap::new(),
+            methuselahs: vec![],
+        }
+    }
+
+    fn add(&mut self, result: SoupResult, settings: &Settings) {
+        for (code, count, is_known) in result.objects {
+            *self.counts.entry(code.clone()).or_insert(0) += count;
+            if !is_known {
+                self.rare.entry(code).or_insert_with(|| result.seed.clone());
+            }
+        }
+
+        //Soups that never settled count too
+        let settled_early = result
+            .stabilized_at
+            .is_some_and(|g| g < settings.methuselah_generations);
+        if !settled_early {
+            self.methuselahs.push((result.seed, result.stabilized_at));
+        }
+    }
+
+    fn load_checkpoint(path: &Path, settings: &Settings) -> Result<Results, String> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Results::empty()),
+            Err(e) => return Err(format!("Couldn't read {}: {}", path.display(), e)),
+        };
+
+        let mut results = Results::empty();
+        for line in text.lines() {
+            let invalid = || format!("Invalid checkpoint line '{}'", line);
+            match line.split_once(' ').ok_or_else(invalid)? {
+                ("settings", described) => {
+                    if described != settings.describe() {
+                        return Err(format!(
+                            "Checkpoint {} was made with different settings ({})",
+                            path.display(),
+                            described
+                        ));
+                    }
+                }
+                ("next_soup", n) => results.next_soup = n.parse().map_err(|_| invalid())?,
+                ("count", rest) => {
+                    let (code, n) = rest.split_once(' ').ok_or_else(invalid)?;
+                    results
+                        .counts
+                        .insert(code.to_owned(), n.parse().map_err(|_| invalid())?);
+                }
+                ("rare", rest) => {
+                    let (code, seed) = rest.split_once(' ').ok_or_else(invalid)?;
+                    results.rare.insert(code.to_owned(), seed.to_owned());
+                }
+                ("methuselah", rest) => {
+                    let (seed, generation) = rest.split_once(' ').ok_or_else(invalid)?;
+                    let generation = match generation {
+                        "unsettled" => None,
+                        g => Some(g.parse().map_err(|_| invalid())?),
+                    };
+                    results.methuselahs.push((seed.to_owned(), generation));
+                }
+                _ => return Err(invalid()),
+            }
+        }
+        Ok(results)
+    }
+
+    /// Write the checkpoint to a temporary file first, so an interruption can't leave a half-written checkpoint behind
+    fn save_checkpoint(&self, path: &Path, settings: &Settings) -> io::Result<()> {
+        let mut text = format!(
+            "settings {}\nnext_soup {}\n",
+            settings.describe(),
+            self.next_soup
+        );
+        for (code, count) in &self.counts {
+            text.push_str(&format!("count {} {}\n", code, count));
+        }
+        for (code, seed) in &self.rare {
+            text.push_str(&format!("rare {} {}\n", code, seed));
+        }
+        for (seed, generation) in &self.methuselahs {
+            let generation = generation.map_or("unsettled".to_owned(), |g| g.to_string());
+            text.push_str(&format!("methuselah {} {}\n", seed, generation));
+        }
+
+        let temp_path = path.with_extension("tmp");
+        fs::write(&temp_path, text)?;
+        fs::rename(&temp_path, path)
+    }
+
+    fn write_report(&self, path: &Path, settings: &Settings) -> io::Result<()> {
+        let mut text = format!(
+            "Soup search results\nsoups searched: {}\nsettings: seeds {}0 onwards, {}x{} at density {}, symmetry {}, board {}\n",
+            self.next_soup,
+            settings.seed_prefix,
+            settings.size,
+            settings.size,
+            settings.density,
+            settings.symmetry,
+            settings.board.name
+        );
+
+        text.push_str("\nObject counts\n");
+        let mut counts: Vec<(&String, &u64)> = self.counts.iter().collect();
+        counts.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+        for (code, count) in counts {
+            text.push_str(&format!("{} {}\n", code, count));
+        }
+
+        text.push_str("\nRare objects, with the first soup they were seen in\n");
+        for (code, seed) in &self.rare {
+            text.push_str(&format!("{} {}\n", code, seed));
+        }
+
+        text.push_str(&format!(
+            "\nMethuselahs, taking at least {} generations to settle\n",
+            settings.methuselah_generations
+        ));
+        for (seed, generation) in &self.methuselahs {
+            match generation {
+                Some(g) => text.push_str(&format!("{} {} generations\n", seed, g)),
+                None => text.push_str(&format!(
+                    "{} did not settle within {} generations\n",
+                    seed, settings.max_generations
+                )),
+            }
+        }
+
+        fs::write(path, text)
+    }
+}
+
+fn main() {
+    let settings = match Settings::from_args(std::env::args().skip(1)) {
+        Ok(settings) => settings,
+        Err(message) => {
+            eprintln!("{}", message);
+            process::exit(2);
+        }
+    };
+
+    let mut results = match Results::load_checkpoint(&settings.checkpoint, &settings) {
+        Ok(results) => results,
+        Err(message) => {
+            eprintln!("{}", message);
+            process::exit(1);
+        }
+    };
+    if results.next_soup > 0 {
+        println!("Resuming from soup {}", results.next_soup);
+    }
+
+    let start = Instant::now();
+    let batch_size = settings.threads * SOUPS_PER_THREAD_PER_BATCH;
+    while results.next_soup < settings.soups {
+        let batch_end = (results.next_soup + batch_size).min(settings.soups);
+        let next_index = AtomicU64::new(results.next_soup);
+        let (sender, receiver) = mpsc::channel();
+
+        thread::scope(|scope| {
+            for _ in 0..settings.threads {
+                let sender = sender.clone();
+                let next_index = &next_index;
+                let settings = &settings;
+                scope.spawn(move || loop {
+                    let index = next_index.fetch_add(1, Ordering::Relaxed);
+                    if index >= batch_end {
+                        break;
+                    }
+                    sender.send((index, run_soup(settings, index))).unwrap();
+                });
+            }
+        });
+        drop(sender);
+
+        //Add results in soup order so the output doesn't depend on thread timing
+        let mut batch: Vec<(u64, SoupResult)> = receiver.iter().collect();
+        batch.sort_by_key(|(index, _)| *index);
+        for (_, result) in batch {
+            results.add(result, &settings);
+        }
+        results.next_soup = batch_end;
+
+        let saved = results
+            .save_checkpoint(&settings.checkpoint, &settings)
+            .and_then(|_| results.write_report(&settings.output, &settings));
+        if let Err(e) = saved {
+            eprintln!("Couldn't save results: {}", e);
+            process::exit(1);
+        }
+        println!(
+            "{} of {} soups searched, {:.1}s elapsed",
+            results.next_soup,
+            settings.soups,
+            start.elapsed().as_secs_f32()
+        );
+    }
+
+    println!("Results written to {}", settings.output.display());
+}
@@ -1,6 +1,8 @@
-use crate::ConsolePoint;
 use engine::life_board::LifeBoard;
+use engine::life_point::LifePoint;
+use engine::rectangle::Rectangle;
 use std::borrow::{Borrow, BorrowMut};
+use std::collections::HashSet;
 use tui::buffer::Buffer;
 use tui::layout::Rect;
 use tui::style::Color;
@@ -10,8 +12,8 @@ use tui::text::{Span, Spans};
 use tui::widgets::Widget;
 
 pub struct LifeWidgetState {
-    pub screen_offset: ConsolePoint,
-    pub center_point: ConsolePoint,
+    pub screen_offset: LifePoint,
+    pub center_point: LifePoint,
     active_style: Style,
     default_style: Style,
 }
@@ -19,8 +21,8 @@ pub struct LifeWidgetState {
 impl LifeWidgetState {
     pub fn new() -> LifeWidgetState {
         LifeWidgetState {
-            screen_offset: ConsolePoint::new(0, 0),
-            center_point: ConsolePoint::new(0, 0),
+            screen_offset: LifePoint::ORIGIN,
+            center_point: LifePoint::ORIGIN,
             active_style: Style::default().bg(Color::LightYellow).fg(Color::Green),
             default_style: Style::default().bg(Color::Black).fg(Color::Green),
         }
@@ -28,12 +30,12 @@ impl LifeWidgetState {
 }
 
 pub struct LifeWidget<'a> {
-    board: Box<&'a dyn LifeBoard>,
+    board: &'a dyn LifeBoard,
     state: &'a mut LifeWidgetState,
 }
 
 impl<'a> LifeWidget<'a> {
-    pub fn new(board: Box<&'a dyn LifeBoard>, state: &'a mut LifeWidgetState) -> LifeWidget<'a> {
+    pub fn new(board: &'a dyn LifeBoard, state: &'a mut LifeWidgetState) -> LifeWidget<'a> {
         LifeWidget { board, state }
     }
 }
@@ -42,14 +44,26 @@ impl<'a> Widget for LifeWidget<'a> {
     fn render(self, area: Rect, buf: &mut Buffer) {
         const LIVE_CELL: &str = "⦿"; //█  //•
         const DEAD_CELL: &str = " ";
-        let mut state = self.state;
-        let offset = &state.screen_offset;
+        let state = self.state;
+        let offset = state.screen_offset;
         let center_y = area.height / 2;
         let center_x = area.width / 2;
 
         //Update center point, need by main game loop to manually toggle active/inactive squares
-        state.center_point =
-            ConsolePoint::new(center_x as i64 + offset.x, center_y as i64 + offset.y);
+        state.center_point = offset.offset(center_x as i64, center_y as i64);
+
+        //Fetch the whole viewport in one query rather than asking about each cell
+        let viewport = Rectangle::new(
+            offset.get_x(),
+            offset.get_y(),
+            area.width as i64,
+            area.height as i64,
+        );
+        let live_cells: HashSet<LifePoint> = self
+            .board
+            .get_live_cells_in(&viewport)
+            .into_iter()
+            .collect();
 
         for screen_y_idx in 0..area.height {
             let mut spans: Vec<Span> = Vec::with_capacity(area.width as usize);
@@ -60,9 +74,8 @@ impl<'a> Widget for LifeWidget<'a> {
                     state.default_style
                 };
 
-                let board_x = screen_x_idx as i64 + offset.x;
-                let board_y = screen_y_idx as i64 + offset.y;
-                if self.board.is_live(board_x, board_y) {
+                let board_point = offset.offset(screen_x_idx as i64, screen_y_idx as i64);
+                if live_cells.contains(&board_point) {
                     spans.push(Span::styled(LIVE_CELL, span_style));
                 } else {
                     spans.push(Span::styled(DEAD_CELL, span_style));
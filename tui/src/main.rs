@@ -1,11 +1,21 @@
 #![allow(unused_imports)]
 mod life_widget;
 use crossterm::event::{poll, read, Event, KeyCode, KeyModifiers};
-use engine::life_board::{BoardPoint, LifeBoard};
+use engine::error::EngineError;
+use engine::life_board::LifeBoard;
+use engine::life_point::LifePoint;
+use engine::observer::ObservedBoard;
 use engine::pattern::Pattern;
+use engine::recorder::Recorder;
+use engine::rectangle::Rectangle;
+use engine::registry::{board_names, find_board, BoardInfo};
+use engine::soup::SoupRng;
 use life_widget::{LifeWidget, LifeWidgetState};
+use std::cell::RefCell;
+use std::fs;
 use std::io;
-use std::time::{Duration, Instant};
+use std::rc::Rc;
+use std::time::{Duration, Instant, SystemTime};
 use tui::backend::{Backend, CrosstermBackend};
 use tui::layout::{Alignment, Constraint, Direction, Layout, Rect};
 use tui::style::{Modifier, Style};
@@ -18,21 +28,55 @@ struct AppState {
     speed: u64,
     last_input_event: String,
     frames_completed: u64,
+    /// Shared with the board's observer, which records every generation
+    recorder: Rc<RefCell<Recorder>>,
+    last_export: String,
+    /// Why the last edit couldn't be made, or empty if it worked
+    last_edit_error: String,
+    rng: SoupRng,
 }
 
+const EXPORT_PATH: &str = "life_timeseries.csv";
+/// Size of the block around the center square that the region editing keys change
+const EDIT_WIDTH: i64 = 16;
+const EDIT_HEIGHT: i64 = 8;
+
 fn main() -> Result<(), io::Error> {
+    //`--board NAME` picks the implementation to run
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let board_name = match args.as_slice() {
+        [] => "dynamic-array2d",
+        [flag, name] if flag == "--board" => name.as_str(),
+        _ => {
+            eprintln!("Usage: tui [--board {}]", board_names().join("|"));
+            std::process::exit(2);
+        }
+    };
+    let board_info = match find_board(board_name) {
+        Ok(info) => info,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(2);
+        }
+    };
+
     let stdout = io::stdout();
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
     terminal.clear()?;
 
-    let mut life_board = engine::new_dynamic_array2d_board();
+    let recorder = Rc::new(RefCell::new(Recorder::new()));
+    let mut life_board = new_recorded_board(board_info, &recorder);
     let mut life_widget_state = LifeWidgetState::new();
     let mut app_state = AppState {
         paused: true,
         speed: 5,
         last_input_event: String::default(),
         frames_completed: 0,
+        recorder,
+        last_export: String::default(),
+        last_edit_error: String::default(),
+        rng: SoupRng::from_seed(&format!("{:?}", SystemTime::now())),
     };
     let mut next_tick = Instant::now();
     loop {
@@ -43,7 +87,7 @@ fn main() -> Result<(), io::Error> {
             &app_state,
         )?;
 
-        let tick_rate = Duration::from_millis(1000 / app_state.speed as u64);
+        let tick_rate = Duration::from_millis(1000 / app_state.speed);
         if !app_state.paused && next_tick <= Instant::now() {
             life_board.step_one();
             app_state.frames_completed += 1;
@@ -59,8 +103,8 @@ fn main() -> Result<(), io::Error> {
             let event = read()?;
             app_state.last_input_event = format!("{:?}", event);
 
-            match event {
-                Event::Key(event) => match event.code {
+            if let Event::Key(event) = event {
+                match event.code {
                     KeyCode::Char('q') => break,
                     KeyCode::Char('n') => {
                         life_board.step_one();
@@ -85,62 +129,78 @@ fn main() -> Result<(), io::Error> {
                         next_tick = Instant::now(); //force immediate screen update
                     }
                     KeyCode::Char('a') | KeyCode::Left => {
-                        life_widget_state.screen_offset = life_widget_state
-                            .screen_offset
-                            .move_left(calc_move_offset(event))
+                        life_widget_state.screen_offset +=
+                            LifePoint::new(-calc_move_offset(event), 0)
                     }
                     KeyCode::Char('d') | KeyCode::Right => {
-                        life_widget_state.screen_offset = life_widget_state
-                            .screen_offset
-                            .move_right(calc_move_offset(event))
+                        life_widget_state.screen_offset +=
+                            LifePoint::new(calc_move_offset(event), 0)
                     }
                     KeyCode::Char('w') | KeyCode::Up => {
-                        life_widget_state.screen_offset = life_widget_state
-                            .screen_offset
-                            .move_up(calc_move_offset(event))
+                        life_widget_state.screen_offset +=
+                            LifePoint::new(0, -calc_move_offset(event))
                     }
                     KeyCode::Char('s') | KeyCode::Down => {
-                        life_widget_state.screen_offset = life_widget_state
-                            .screen_offset
-                            .move_down(calc_move_offset(event))
+                        life_widget_state.screen_offset +=
+                            LifePoint::new(0, calc_move_offset(event))
                     }
                     KeyCode::Char('c') => {
-                        life_board = engine::new_dynamic_array2d_board();
+                        let live = Rectangle::from_points(&life_board.get_live_cells());
+                        life_board.clear_region(&live);
+                        *app_state.recorder.borrow_mut() = Recorder::new();
+                        app_state.recorder.borrow_mut().record(&life_board);
                         app_state.frames_completed = 0;
+                        app_state.last_edit_error = String::default();
+                    }
+                    KeyCode::Char('e') => {
+                        let csv = app_state.recorder.borrow().get_series().to_csv();
+                        app_state.last_export = match fs::write(EXPORT_PATH, csv) {
+                            Ok(_) => format!("exported to {}", EXPORT_PATH),
+                            Err(e) => format!("export failed: {}", e),
+                        };
                     }
                     KeyCode::Char(' ') => {
-                        let bp = life_widget_state.center_point.to_board_point();
+                        let bp = life_widget_state.center_point;
                         let is_live = life_board.is_live_point(&bp);
-                        life_board.set_liveness_point(&bp, !is_live);
+                        let toggled = life_board.try_set_liveness(bp.get_x(), bp.get_y(), !is_live);
+                        app_state.last_edit_error = edit_status(toggled);
+                    }
+                    //Region edits stop at the edge of the board, so they never fail
+                    KeyCode::Char('x') => {
+                        life_board.clear_region(&edit_region(life_widget_state.center_point));
+                        app_state.last_edit_error = String::default();
+                    }
+                    KeyCode::Char('i') => {
+                        life_board.invert_region(&edit_region(life_widget_state.center_point));
+                        app_state.last_edit_error = String::default();
+                    }
+                    KeyCode::Char('r') => {
+                        life_board.randomize_region(
+                            &edit_region(life_widget_state.center_point),
+                            0.5,
+                            &mut || app_state.rng.next_f64(),
+                        );
+                        app_state.last_edit_error = String::default();
+                    }
+                    KeyCode::Char(key @ '1'..='5') => {
+                        let pattern = match key {
+                            '1' => Pattern::ACORN(),
+                            '2' => Pattern::BLOCK(),
+                            '3' => Pattern::BEACON(),
+                            '4' => Pattern::PULSAR(),
+                            _ => Pattern::GLIDER_SOUTHEAST(),
+                        };
+                        let drawn =
+                            life_board.try_draw_pattern(&pattern, &life_widget_state.center_point);
+                        app_state.last_edit_error = edit_status(drawn);
                     }
-                    KeyCode::Char('1') => life_board.draw_pattern(
-                        &Pattern::ACORN(),
-                        &life_widget_state.center_point.to_board_point(),
-                    ),
-                    KeyCode::Char('2') => life_board.draw_pattern(
-                        &Pattern::BLOCK(),
-                        &life_widget_state.center_point.to_board_point(),
-                    ),
-                    KeyCode::Char('3') => life_board.draw_pattern(
-                        &Pattern::BEACON(),
-                        &life_widget_state.center_point.to_board_point(),
-                    ),
-                    KeyCode::Char('4') => life_board.draw_pattern(
-                        &Pattern::PULSAR(),
-                        &life_widget_state.center_point.to_board_point(),
-                    ),
-                    KeyCode::Char('5') => life_board.draw_pattern(
-                        &Pattern::GLIDER_SOUTHEAST(),
-                        &life_widget_state.center_point.to_board_point(),
-                    ),
                     _ => {}
-                },
-                // Event::Mouse(event) => last_input_event = format!("{:?}", event),
-                // Event::Resize(width, height) => {
-                //     last_input_event = format!("New size {}x{}", width, height)
-                // }
-                _ => {}
+                }
             }
+            // Event::Mouse(event) => last_input_event = format!("{:?}", event),
+            // Event::Resize(width, height) => {
+            //     last_input_event = format!("New size {}x{}", width, height)
+            // }
         } else {
             // Timeout expired and no `Event` is available
         }
@@ -150,6 +210,31 @@ fn main() -> Result<(), io::Error> {
     Ok(())
 }
 
+/// A new empty board, with its first generation already recorded and an observer to record each step after that
+fn new_recorded_board(info: &BoardInfo, recorder: &Rc<RefCell<Recorder>>) -> ObservedBoard {
+    let mut board = ObservedBoard::new(info.create());
+    recorder.borrow_mut().record(&board);
+    board.add_observer(Box::new(Rc::clone(recorder)));
+    board
+}
+
+/// What to show in the status line after an edit: nothing if it worked, otherwise why not
+fn edit_status(result: Result<(), EngineError>) -> String {
+    match result {
+        Ok(_) => String::default(),
+        Err(e) => format!("can't edit: {}", e),
+    }
+}
+
+fn edit_region(center: LifePoint) -> Rectangle {
+    Rectangle::new(
+        center.get_x() - EDIT_WIDTH / 2,
+        center.get_y() - EDIT_HEIGHT / 2,
+        EDIT_WIDTH,
+        EDIT_HEIGHT,
+    )
+}
+
 fn calc_move_offset(event: crossterm::event::KeyEvent) -> i64 {
     let alt = event.modifiers.contains(KeyModifiers::ALT);
     let ctrl = event.modifiers.contains(KeyModifiers::CONTROL);
@@ -162,9 +247,9 @@ fn calc_move_offset(event: crossterm::event::KeyEvent) -> i64 {
     }
 }
 
-fn draw<'a, B: Backend>(
+fn draw<B: Backend>(
     terminal: &mut Terminal<B>,
-    life_widget_state: &'a mut LifeWidgetState,
+    life_widget_state: &mut LifeWidgetState,
     board: &dyn LifeBoard,
     app_state: &AppState,
 ) -> Result<(), io::Error> {
@@ -182,7 +267,7 @@ fn draw<'a, B: Backend>(
         let main_block_rect = main_block.inner(chunks[0]);
         f.render_widget(main_block, chunks[0]);
 
-        let life_widget = LifeWidget::new(Box::new(board), life_widget_state);
+        let life_widget = LifeWidget::new(board, life_widget_state);
         f.render_widget(life_widget, main_block_rect);
 
         let status_spans = if app_state.paused {
@@ -204,10 +289,19 @@ fn draw<'a, B: Backend>(
         };
 
         let controls_text =
-            "(p)lay/(p)ause, (n)ext step, (c)lear, (q)uit, arrows move, space toggles center square liveness, 1-4 to insert pattern at center, (> or ]) speed up, (< or [) slow down";
+            "(p)lay/(p)ause, (n)ext step, (c)lear, (e)xport population history, (q)uit, arrows move, space toggles center square liveness, (x) clears, (i)nverts or (r)andomizes the block around it, 1-5 to insert an acorn, block, beacon, pulsar or glider at center, (> or ]) speed up, (< or [) slow down";
 
         //let debug_text = Spans::from(vec![Span::from(last_input_event)]);
-        let stats_text = board.get_stats().iter().map(|(k, v)| format!("{}: {}", k, v)).collect::<Vec<String>>().join(", ");
+        let mut stats_text = board.get_stats().iter().map(|(k, v)| format!("{}: {}", k, v)).collect::<Vec<String>>().join(", ");
+        if let Some(latest) = app_state.recorder.borrow().get_series().get_records().last() {
+            stats_text.push_str(&format!(", births: {}, deaths: {}", latest.births, latest.deaths));
+        }
+        if !app_state.last_export.is_empty() {
+            stats_text.push_str(&format!(", {}", app_state.last_export));
+        }
+        if !app_state.last_edit_error.is_empty() {
+            stats_text.push_str(&format!(", {}", app_state.last_edit_error));
+        }
 
         let controls_block = Paragraph::new(vec![
             status_spans,
@@ -222,46 +316,3 @@ fn draw<'a, B: Backend>(
 
     Ok(())
 }
-
-pub struct ConsolePoint {
-    x: i64,
-    y: i64,
-}
-
-impl ConsolePoint {
-    pub fn new(x: i64, y: i64) -> ConsolePoint {
-        ConsolePoint { x, y }
-    }
-
-    pub fn to_board_point(&self) -> BoardPoint {
-        BoardPoint::new(self.x, self.y)
-    }
-
-    pub fn move_left(&self, offset: i64) -> ConsolePoint {
-        ConsolePoint {
-            x: self.x - offset,
-            y: self.y,
-        }
-    }
-
-    pub fn move_right(&self, offset: i64) -> ConsolePoint {
-        ConsolePoint {
-            x: self.x + offset,
-            y: self.y,
-        }
-    }
-
-    pub fn move_up(&self, offset: i64) -> ConsolePoint {
-        ConsolePoint {
-            x: self.x,
-            y: self.y - offset,
-        }
-    }
-
-    pub fn move_down(&self, offset: i64) -> ConsolePoint {
-        ConsolePoint {
-            x: self.x,
-            y: self.y + offset,
-        }
-    }
-}